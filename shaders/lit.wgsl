@@ -0,0 +1,176 @@
+// Vertex shader
+
+struct CameraUniform {
+    view_proj: mat4x4<f32>,
+    view_position: vec4<f32>,
+};
+@group(1) @binding(0)
+var<uniform> camera: CameraUniform;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) tex_coords: vec2<f32>,
+}
+
+struct InstanceInput {
+    @location(5) model_matrix_0: vec4<f32>,
+    @location(6) model_matrix_1: vec4<f32>,
+    @location(7) model_matrix_2: vec4<f32>,
+    @location(8) model_matrix_3: vec4<f32>,
+    @location(9) tint: vec4<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+    @location(1) tint: vec4<f32>,
+    @location(2) world_position: vec3<f32>,
+    @location(3) world_normal: vec3<f32>,
+}
+
+@vertex
+fn vs_main(
+    model: VertexInput,
+    instance: InstanceInput,
+) -> VertexOutput {
+    let model_matrix = mat4x4<f32>(
+        instance.model_matrix_0,
+        instance.model_matrix_1,
+        instance.model_matrix_2,
+        instance.model_matrix_3,
+    );
+    // fine for rotations and uniform scaling, the normal is
+    // normalized again per fragment
+    let normal_matrix = mat3x3<f32>(
+        instance.model_matrix_0.xyz,
+        instance.model_matrix_1.xyz,
+        instance.model_matrix_2.xyz,
+    );
+    let world_position = model_matrix * vec4<f32>(model.position, 1.0);
+
+    var out: VertexOutput;
+    out.tex_coords = model.tex_coords;
+    out.tint = instance.tint;
+    out.world_position = world_position.xyz;
+    out.world_normal = normal_matrix * model.normal;
+    out.clip_position = camera.view_proj * world_position;
+    return out;
+}
+
+
+// Fragment shader
+
+struct DirectionalLight {
+    direction: vec3<f32>,
+    intensity: f32,
+    color: vec3<f32>,
+}
+
+struct PointLight {
+    position: vec3<f32>,
+    intensity: f32,
+    color: vec3<f32>,
+    range: f32,
+    attenuation: vec3<f32>,
+}
+
+struct SpotLight {
+    position: vec3<f32>,
+    intensity: f32,
+    color: vec3<f32>,
+    range: f32,
+    direction: vec3<f32>,
+    cos_inner: f32,
+    attenuation: vec3<f32>,
+    cos_outer: f32,
+}
+
+struct Lights {
+    ambient: vec3<f32>,
+    point_count: u32,
+    spot_count: u32,
+    directional: DirectionalLight,
+    points: array<PointLight, 8>,
+    spots: array<SpotLight, 8>,
+}
+@group(1) @binding(1)
+var<uniform> lights: Lights;
+
+struct LitMaterial {
+    specular_color: vec3<f32>,
+    shininess: f32,
+}
+@group(2) @binding(0)
+var<uniform> material: LitMaterial;
+
+@group(0) @binding(0)
+var t_diffuse: texture_2d<f32>;
+@group(0) @binding(1)
+var s_diffuse: sampler;
+
+// Constant, linear and quadratic falloff, faded out to zero at the range
+fn attenuate(distance: f32, range: f32, factors: vec3<f32>) -> f32 {
+    let falloff = 1.0 / (factors.x + factors.y * distance + factors.z * distance * distance);
+    let window = clamp(1.0 - pow(distance / range, 4.0), 0.0, 1.0);
+    return falloff * window * window;
+}
+
+// Diffuse and specular light arriving from light_dir, which
+// points from the surface to the light
+fn blinn_phong(
+    light_dir: vec3<f32>,
+    radiance: vec3<f32>,
+    normal: vec3<f32>,
+    view_dir: vec3<f32>,
+    base_color: vec3<f32>,
+) -> vec3<f32> {
+    let diffuse = max(dot(normal, light_dir), 0.0);
+    let half_dir = normalize(light_dir + view_dir);
+    // no highlights on the side facing away from the light
+    let specular = pow(max(dot(normal, half_dir), 0.0), material.shininess)
+        * select(0.0, 1.0, diffuse > 0.0);
+    return radiance * (diffuse * base_color + specular * material.specular_color);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let base = textureSample(t_diffuse, s_diffuse, in.tex_coords) * in.tint;
+    let normal = normalize(in.world_normal);
+    let view_dir = normalize(camera.view_position.xyz - in.world_position);
+
+    var color = lights.ambient * base.rgb;
+
+    let sun = lights.directional;
+    if sun.intensity > 0.0 {
+        color += blinn_phong(-sun.direction, sun.color * sun.intensity, normal, view_dir, base.rgb);
+    }
+
+    for (var i = 0u; i < lights.point_count; i++) {
+        let light = lights.points[i];
+        let to_light = light.position - in.world_position;
+        let distance = length(to_light);
+        if distance >= light.range {
+            continue;
+        }
+        let radiance = light.color * light.intensity
+            * attenuate(distance, light.range, light.attenuation);
+        color += blinn_phong(to_light / distance, radiance, normal, view_dir, base.rgb);
+    }
+
+    for (var i = 0u; i < lights.spot_count; i++) {
+        let light = lights.spots[i];
+        let to_light = light.position - in.world_position;
+        let distance = length(to_light);
+        if distance >= light.range {
+            continue;
+        }
+        let light_dir = to_light / distance;
+        let cone = smoothstep(light.cos_outer, light.cos_inner, dot(-light_dir, light.direction));
+        let radiance = light.color * light.intensity * cone
+            * attenuate(distance, light.range, light.attenuation);
+        color += blinn_phong(light_dir, radiance, normal, view_dir, base.rgb);
+    }
+
+    return vec4<f32>(color, base.a);
+}
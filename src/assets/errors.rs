@@ -0,0 +1,87 @@
+//! Bine assets errors.
+//!
+//! Author: BEKs => 18.10.2026
+//!
+//! Errors returned while importing model files
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+// === ObjError enum
+#[derive(Debug)]
+pub enum ObjError {
+    // the OBJ file could not be read
+    Io(PathBuf, io::Error),
+    // the OBJ data is malformed
+    Parse(tobj::LoadError),
+    // a material library could not be read or is malformed
+    Material(String, tobj::LoadError),
+}
+
+// Implement Display
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjError::Io(path, err) => write!(f, "Failed to read {}: {}", path.display(), err),
+            ObjError::Parse(err) => write!(f, "Malformed OBJ file: {}", err),
+            ObjError::Material(name, err) => {
+                write!(f, "Failed to load material library {}: {}", name, err)
+            }
+        }
+    }
+}
+
+// Implement std::error::Error
+impl Error for ObjError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ObjError::Io(_, err) => Some(err),
+            ObjError::Parse(err) | ObjError::Material(_, err) => Some(err),
+        }
+    }
+}
+
+// === GltfError enum
+#[derive(Debug)]
+pub enum GltfError {
+    // the file or a buffer or image it references could not be read,
+    // or the data is malformed
+    Import(gltf::Error),
+    // an image uses a pixel format other than 8 bits per channel
+    UnsupportedImage(usize, String),
+    // a mesh primitive is not made of triangles, or lacks positions
+    UnsupportedPrimitive(String, String),
+    // the node hierarchy reaches this node twice, it is
+    // its own ancestor or has more than one parent
+    NodeCycle(usize),
+}
+
+// Implement Display
+impl fmt::Display for GltfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GltfError::Import(err) => write!(f, "Failed to import glTF: {}", err),
+            GltfError::UnsupportedImage(index, format) => {
+                write!(f, "Image {} has unsupported format {}", index, format)
+            }
+            GltfError::UnsupportedPrimitive(mesh, msg) => {
+                write!(f, "Unsupported primitive in mesh {}: {}", mesh, msg)
+            }
+            GltfError::NodeCycle(index) => {
+                write!(f, "Node {} appears more than once in the hierarchy", index)
+            }
+        }
+    }
+}
+
+// Implement std::error::Error
+impl Error for GltfError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GltfError::Import(err) => Some(err),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,606 @@
+//! Bine assets
+//!
+//! Author: BEKs => 18.10.2026
+//!
+//! glTF 2.0 import for .gltf and .glb files. A GltfScene holds the
+//! meshes, materials, textures, node hierarchy and cameras on the CPU,
+//! GltfScene::upload turns it into renderer handles ready to draw.
+
+use std::path::Path;
+
+use anyhow::Result;
+use cgmath::{Deg, EuclideanSpace, Matrix4, Point3, Rad, SquareMatrix, Transform, Vector3};
+use image::{DynamicImage, GrayAlphaImage, GrayImage, RgbImage, Rgba, RgbaImage};
+
+use super::{GltfError, MeshData};
+use crate::renderer::{
+    BlendMode, Camera, Instance, MaterialHandle, MeshHandle, Projection, Renderer, TextureHandle,
+    TextureOptions,
+};
+
+// === GltfMesh struct
+// A glTF mesh is drawn as one MeshData per primitive
+#[derive(Debug, Clone)]
+pub struct GltfMesh {
+    pub name: String,
+    pub primitives: Vec<MeshData>,
+}
+
+// === GltfMaterial struct
+// The metallic roughness material of glTF. The basic material only
+// uses the base color, the other factors are kept for custom shaders.
+// Masked materials are blended as the basic material has no alpha
+// test, alpha_cutoff is kept for shaders that discard instead.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GltfMaterial {
+    pub name: String,
+    pub base_color_factor: [f32; 4],
+    // index into the textures of the scene
+    pub base_color_texture: Option<usize>,
+    pub metallic_factor: f32,
+    pub roughness_factor: f32,
+    pub emissive_factor: [f32; 3],
+    pub blend: BlendMode,
+    // set for masked materials, texels below it are meant to be cut
+    pub alpha_cutoff: Option<f32>,
+    // drawn without back face culling
+    pub double_sided: bool,
+}
+
+// === GltfTexture struct
+#[derive(Debug, Clone)]
+pub struct GltfTexture {
+    pub name: String,
+    pub image: RgbaImage,
+    // wrapping and filtering taken from the glTF sampler
+    pub options: TextureOptions,
+}
+
+// === GltfNode struct
+#[derive(Debug, Clone)]
+pub struct GltfNode {
+    pub name: String,
+    // relative to the parent node
+    pub transform: Matrix4<f32>,
+    pub mesh: Option<usize>,
+    pub camera: Option<usize>,
+    pub children: Vec<usize>,
+}
+
+// === GltfCamera struct
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GltfCamera {
+    pub projection: Projection,
+    pub aspect: Option<f32>,
+    pub znear: f32,
+    pub zfar: f32,
+}
+
+// === GltfScene struct
+// Meshes, materials, textures, nodes and cameras refer to each
+// other by their index in these lists, as in the glTF file.
+#[derive(Debug, Clone, Default)]
+pub struct GltfScene {
+    pub meshes: Vec<GltfMesh>,
+    pub materials: Vec<GltfMaterial>,
+    pub textures: Vec<GltfTexture>,
+    pub nodes: Vec<GltfNode>,
+    // top level nodes of the scene that is loaded
+    pub roots: Vec<usize>,
+    pub cameras: Vec<GltfCamera>,
+}
+
+impl GltfScene {
+    // Far plane used for cameras with an infinite projection
+    const DEFAULT_ZFAR: f32 = 1000.0;
+
+    // Loads a .gltf or .glb file and the buffers and images it refers to
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, GltfError> {
+        let (document, buffers, images) = gltf::import(path).map_err(GltfError::Import)?;
+        Self::new(&document, &buffers, images)
+    }
+
+    // Loads a .glb file embedded in the game, e.g. with include_bytes!.
+    // Images have to be stored in its binary chunk, not referenced by uri.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, GltfError> {
+        let (document, buffers, images) = gltf::import_slice(bytes).map_err(GltfError::Import)?;
+        Self::new(&document, &buffers, images)
+    }
+
+    fn new(
+        document: &gltf::Document,
+        buffers: &[gltf::buffer::Data],
+        images: Vec<gltf::image::Data>,
+    ) -> Result<Self, GltfError> {
+        let images = images
+            .into_iter()
+            .enumerate()
+            .map(|(index, data)| Self::rgba_image(index, data))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let textures = document
+            .textures()
+            .map(|texture| {
+                let sampler = texture.sampler();
+                let defaults = TextureOptions::default();
+                let (min_filter, mipmap_filter, generate_mips) = Self::min_filter(
+                    sampler.min_filter(),
+                )
+                .unwrap_or((defaults.min_filter, defaults.mipmap_filter, true));
+                GltfTexture {
+                    name: Self::name(texture.name(), "texture", texture.index()),
+                    image: images[texture.source().index()].clone(),
+                    options: TextureOptions {
+                        address_mode_u: Self::address_mode(sampler.wrap_s()),
+                        address_mode_v: Self::address_mode(sampler.wrap_t()),
+                        mag_filter: Self::mag_filter(sampler.mag_filter())
+                            .unwrap_or(defaults.mag_filter),
+                        min_filter,
+                        mipmap_filter,
+                        generate_mips,
+                        ..defaults
+                    },
+                }
+            })
+            .collect();
+
+        let materials = document
+            .materials()
+            .map(|material| {
+                let pbr = material.pbr_metallic_roughness();
+                GltfMaterial {
+                    name: Self::name(material.name(), "material", material.index().unwrap_or(0)),
+                    base_color_factor: pbr.base_color_factor(),
+                    base_color_texture: pbr.base_color_texture().map(|info| info.texture().index()),
+                    metallic_factor: pbr.metallic_factor(),
+                    roughness_factor: pbr.roughness_factor(),
+                    emissive_factor: material.emissive_factor(),
+                    blend: match material.alpha_mode() {
+                        gltf::material::AlphaMode::Opaque => BlendMode::Opaque,
+                        gltf::material::AlphaMode::Mask | gltf::material::AlphaMode::Blend => {
+                            BlendMode::Alpha
+                        }
+                    },
+                    alpha_cutoff: material.alpha_cutoff(),
+                    double_sided: material.double_sided(),
+                }
+            })
+            .collect();
+
+        let meshes = document
+            .meshes()
+            .map(|mesh| Self::mesh(&mesh, buffers))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let nodes: Vec<GltfNode> = document
+            .nodes()
+            .map(|node| GltfNode {
+                name: Self::name(node.name(), "node", node.index()),
+                transform: Matrix4::from(node.transform().matrix()),
+                mesh: node.mesh().map(|mesh| mesh.index()),
+                camera: node.camera().map(|camera| camera.index()),
+                children: node.children().map(|child| child.index()).collect(),
+            })
+            .collect();
+
+        // without a scene every node that is nobody's child is a root
+        let roots = match document
+            .default_scene()
+            .or_else(|| document.scenes().next())
+        {
+            Some(scene) => scene.nodes().map(|node| node.index()).collect(),
+            None => (0..nodes.len())
+                .filter(|&index| !nodes.iter().any(|node| node.children.contains(&index)))
+                .collect(),
+        };
+
+        let cameras = document
+            .cameras()
+            .map(|camera| match camera.projection() {
+                gltf::camera::Projection::Perspective(perspective) => GltfCamera {
+                    projection: Projection::Perspective {
+                        fovy: Deg::from(Rad(perspective.yfov())).0,
+                    },
+                    aspect: perspective.aspect_ratio(),
+                    znear: perspective.znear(),
+                    zfar: perspective.zfar().unwrap_or(Self::DEFAULT_ZFAR),
+                },
+                gltf::camera::Projection::Orthographic(orthographic) => GltfCamera {
+                    projection: Projection::Orthographic {
+                        height: orthographic.ymag() * 2.0,
+                    },
+                    aspect: Some(orthographic.xmag() / orthographic.ymag()),
+                    znear: orthographic.znear(),
+                    zfar: orthographic.zfar(),
+                },
+            })
+            .collect();
+
+        let scene = Self {
+            meshes,
+            materials,
+            textures,
+            nodes,
+            roots,
+            cameras,
+        };
+        // fail on broken hierarchies right away instead of on upload
+        scene.scene_nodes()?;
+        Ok(scene)
+    }
+
+    fn name(name: Option<&str>, kind: &str, index: usize) -> String {
+        name.map(String::from)
+            .unwrap_or_else(|| format!("{kind} {index}"))
+    }
+
+    fn rgba_image(index: usize, data: gltf::image::Data) -> Result<RgbaImage, GltfError> {
+        use gltf::image::Format;
+
+        let (width, height, pixels) = (data.width, data.height, data.pixels);
+        let image = match data.format {
+            Format::R8 => GrayImage::from_raw(width, height, pixels).map(DynamicImage::from),
+            Format::R8G8 => GrayAlphaImage::from_raw(width, height, pixels).map(DynamicImage::from),
+            Format::R8G8B8 => RgbImage::from_raw(width, height, pixels).map(DynamicImage::from),
+            Format::R8G8B8A8 => RgbaImage::from_raw(width, height, pixels).map(DynamicImage::from),
+            format => return Err(GltfError::UnsupportedImage(index, format!("{format:?}"))),
+        };
+        image
+            .map(|image| image.to_rgba8())
+            .ok_or_else(|| GltfError::UnsupportedImage(index, "with a wrong size".into()))
+    }
+
+    fn address_mode(mode: gltf::texture::WrappingMode) -> wgpu::AddressMode {
+        match mode {
+            gltf::texture::WrappingMode::ClampToEdge => wgpu::AddressMode::ClampToEdge,
+            gltf::texture::WrappingMode::MirroredRepeat => wgpu::AddressMode::MirrorRepeat,
+            gltf::texture::WrappingMode::Repeat => wgpu::AddressMode::Repeat,
+        }
+    }
+
+    fn mag_filter(filter: Option<gltf::texture::MagFilter>) -> Option<wgpu::FilterMode> {
+        match filter? {
+            gltf::texture::MagFilter::Nearest => Some(wgpu::FilterMode::Nearest),
+            gltf::texture::MagFilter::Linear => Some(wgpu::FilterMode::Linear),
+        }
+    }
+
+    // Minification and mip filter, and whether mips are used at all.
+    // None if the sampler leaves it to the loader.
+    fn min_filter(
+        filter: Option<gltf::texture::MinFilter>,
+    ) -> Option<(wgpu::FilterMode, wgpu::FilterMode, bool)> {
+        use gltf::texture::MinFilter;
+        use wgpu::FilterMode::{Linear, Nearest};
+
+        Some(match filter? {
+            MinFilter::Nearest => (Nearest, Nearest, false),
+            MinFilter::Linear => (Linear, Nearest, false),
+            MinFilter::NearestMipmapNearest => (Nearest, Nearest, true),
+            MinFilter::LinearMipmapNearest => (Linear, Nearest, true),
+            MinFilter::NearestMipmapLinear => (Nearest, Linear, true),
+            MinFilter::LinearMipmapLinear => (Linear, Linear, true),
+        })
+    }
+
+    fn mesh(mesh: &gltf::Mesh, buffers: &[gltf::buffer::Data]) -> Result<GltfMesh, GltfError> {
+        let name = Self::name(mesh.name(), "mesh", mesh.index());
+        let unsupported = |msg: String| GltfError::UnsupportedPrimitive(name.clone(), msg);
+
+        let mut primitives = Vec::new();
+        for primitive in mesh.primitives() {
+            if primitive.mode() != gltf::mesh::Mode::Triangles {
+                return Err(unsupported(format!("{:?} mode", primitive.mode())));
+            }
+            let reader =
+                primitive.reader(|buffer| buffers.get(buffer.index()).map(|data| &data[..]));
+            let positions: Vec<[f32; 3]> = reader
+                .read_positions()
+                .ok_or_else(|| unsupported("no positions".into()))?
+                .collect();
+            // primitives without indices draw their vertices in order
+            let indices = match reader.read_indices() {
+                Some(indices) => indices.into_u32().collect(),
+                None => (0..positions.len() as u32).collect(),
+            };
+            primitives.push(MeshData {
+                name: name.clone(),
+                tex_coords: reader
+                    .read_tex_coords(0)
+                    .map(|tex_coords| tex_coords.into_f32().collect())
+                    .unwrap_or_default(),
+                normals: reader
+                    .read_normals()
+                    .map(|normals| normals.collect())
+                    .unwrap_or_default(),
+                positions,
+                indices,
+                material: primitive.material().index(),
+            });
+        }
+        Ok(GltfMesh { name, primitives })
+    }
+
+    // Every node of the scene with its world transform, parents before
+    // their children. A node reached twice, through a cycle or a second
+    // parent, is an error.
+    pub fn scene_nodes(&self) -> Result<Vec<(usize, Matrix4<f32>)>, GltfError> {
+        let mut nodes = Vec::with_capacity(self.nodes.len());
+        let mut visited = vec![false; self.nodes.len()];
+        let mut stack: Vec<(usize, Matrix4<f32>)> = self
+            .roots
+            .iter()
+            .rev()
+            .map(|&root| (root, Matrix4::identity()))
+            .collect();
+        while let Some((index, parent)) = stack.pop() {
+            let Some(node) = self.nodes.get(index) else {
+                continue;
+            };
+            if std::mem::replace(&mut visited[index], true) {
+                return Err(GltfError::NodeCycle(index));
+            }
+            let transform = parent * node.transform;
+            nodes.push((index, transform));
+            stack.extend(node.children.iter().rev().map(|&child| (child, transform)));
+        }
+        Ok(nodes)
+    }
+
+    // The camera placed where the first node using it sits in the
+    // scene, looking down its -z axis. None if no node uses it.
+    // Cameras without an aspect ratio of their own get default_aspect,
+    // e.g. Renderer::aspect_ratio(). With auto aspect on the renderer
+    // replaces it anyway in update_camera.
+    pub fn camera(&self, index: usize, default_aspect: f32) -> Result<Option<Camera>, GltfError> {
+        let Some(gltf_camera) = self.cameras.get(index) else {
+            return Ok(None);
+        };
+        let Some((_, transform)) = self
+            .scene_nodes()?
+            .into_iter()
+            .find(|(node, _)| self.nodes[*node].camera == Some(index))
+        else {
+            return Ok(None);
+        };
+
+        let eye = transform.transform_point(Point3::origin());
+        let forward = transform.transform_vector(-Vector3::unit_z());
+        let up = transform.transform_vector(Vector3::unit_y());
+        let mut camera = Camera::new(
+            eye,
+            eye + forward,
+            up,
+            gltf_camera.aspect.unwrap_or(default_aspect),
+            45.0,
+            gltf_camera.znear,
+            gltf_camera.zfar,
+        );
+        camera.set_projection(gltf_camera.projection);
+        Ok(Some(camera))
+    }
+
+    // Uploads the meshes and base color textures and places every
+    // mesh primitive of the scene with its node's world transform.
+    // Double sided materials are drawn without back face culling.
+    pub fn upload(&self, renderer: &mut Renderer) -> Result<LoadedScene> {
+        let mut textures: Vec<Option<TextureHandle>> = vec![None; self.textures.len()];
+        for material in &self.materials {
+            if let Some(index) = material.base_color_texture
+                && textures[index].is_none()
+            {
+                let texture = &self.textures[index];
+                textures[index] = Some(renderer.load_texture_from_image(
+                    &DynamicImage::ImageRgba8(texture.image.clone()),
+                    &texture.name,
+                    texture.options,
+                )?);
+            }
+        }
+        // untextured materials are drawn with their factor as the tint
+        let white = renderer.load_texture_from_image(
+            &DynamicImage::ImageRgba8(RgbaImage::from_pixel(1, 1, Rgba([255; 4]))),
+            "glTF white",
+            TextureOptions::pixel_art(),
+        )?;
+
+        let meshes: Vec<Vec<MeshHandle>> = self
+            .meshes
+            .iter()
+            .map(|mesh| {
+                mesh.primitives
+                    .iter()
+                    .map(|primitive| primitive.upload(renderer))
+                    .collect()
+            })
+            .collect();
+
+        let mut primitives = Vec::new();
+        for (node, transform) in self.scene_nodes()? {
+            let Some(mesh) = self.nodes[node].mesh else {
+                continue;
+            };
+            for (primitive, &handle) in self.meshes[mesh].primitives.iter().zip(&meshes[mesh]) {
+                let material = primitive
+                    .material
+                    .and_then(|index| self.materials.get(index));
+                let texture = material
+                    .and_then(|material| material.base_color_texture)
+                    .and_then(|index| textures[index])
+                    .unwrap_or(white);
+                primitives.push(ScenePrimitive {
+                    mesh: handle,
+                    texture,
+                    material: match material {
+                        Some(material) if material.double_sided => {
+                            renderer.double_sided_material(material.blend)
+                        }
+                        Some(material) => renderer.basic_material(material.blend),
+                        None => renderer.basic_material(BlendMode::Opaque),
+                    },
+                    tint: material.map_or(Instance::WHITE, |material| material.base_color_factor),
+                    transform,
+                });
+            }
+        }
+
+        Ok(LoadedScene {
+            meshes: meshes.into_iter().flatten().collect(),
+            textures: textures.into_iter().flatten().chain([white]).collect(),
+            primitives,
+        })
+    }
+}
+
+// === ScenePrimitive struct
+// One mesh primitive placed in the scene
+#[derive(Debug, Clone, Copy)]
+pub struct ScenePrimitive {
+    pub mesh: MeshHandle,
+    pub texture: TextureHandle,
+    pub material: MaterialHandle,
+    pub tint: [f32; 4],
+    pub transform: Matrix4<f32>,
+}
+
+// === LoadedScene struct
+// A scene uploaded to the renderer. The meshes and textures
+// are listed so the game can unload them again.
+#[derive(Debug, Clone)]
+pub struct LoadedScene {
+    pub meshes: Vec<MeshHandle>,
+    pub textures: Vec<TextureHandle>,
+    pub primitives: Vec<ScenePrimitive>,
+}
+
+impl LoadedScene {
+    // Queues the whole scene for the next call to render()
+    pub fn draw(&self, renderer: &mut Renderer) {
+        self.draw_with_transform(renderer, Matrix4::identity());
+    }
+
+    // Same as draw() with the whole scene moved by the transform
+    pub fn draw_with_transform(&self, renderer: &mut Renderer, transform: Matrix4<f32>) {
+        for primitive in &self.primitives {
+            renderer.draw_with_material(
+                primitive.material,
+                primitive.mesh,
+                primitive.texture,
+                &[Instance::new(transform * primitive.transform).with_tint(primitive.tint)],
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(name: &str, children: Vec<usize>) -> GltfNode {
+        GltfNode {
+            name: name.into(),
+            transform: Matrix4::from_translation(Vector3::new(1.0, 0.0, 0.0)),
+            mesh: None,
+            camera: None,
+            children,
+        }
+    }
+
+    fn scene(nodes: Vec<GltfNode>) -> GltfScene {
+        GltfScene {
+            nodes,
+            roots: vec![0],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn children_inherit_parent_transforms() {
+        let scene = scene(vec![node("root", vec![1]), node("child", vec![])]);
+        let nodes = scene.scene_nodes().unwrap();
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(nodes[1].0, 1);
+        assert_eq!(nodes[1].1.w.x, 2.0);
+    }
+
+    #[test]
+    fn cycles_are_an_error() {
+        let scene = scene(vec![node("a", vec![1]), node("b", vec![0])]);
+        assert!(matches!(scene.scene_nodes(), Err(GltfError::NodeCycle(0))));
+    }
+
+    #[test]
+    fn shared_children_are_an_error() {
+        let scene = scene(vec![
+            node("root", vec![1, 2]),
+            node("a", vec![2]),
+            node("b", vec![]),
+        ]);
+        assert!(matches!(scene.scene_nodes(), Err(GltfError::NodeCycle(2))));
+    }
+
+    #[test]
+    fn alpha_mode_and_sides_are_read() {
+        let json = r#"{
+            "asset": { "version": "2.0" },
+            "materials": [
+                { "name": "solid" },
+                { "name": "leaf", "alphaMode": "MASK", "alphaCutoff": 0.3, "doubleSided": true },
+                { "name": "glass", "alphaMode": "BLEND" }
+            ]
+        }"#;
+        let scene = GltfScene::from_slice(json.as_bytes()).unwrap();
+        let [solid, leaf, glass] = &scene.materials[..] else {
+            panic!("expected three materials");
+        };
+
+        assert_eq!(solid.blend, BlendMode::Opaque);
+        assert_eq!(solid.alpha_cutoff, None);
+        assert!(!solid.double_sided);
+        assert_eq!(leaf.blend, BlendMode::Alpha);
+        assert_eq!(leaf.alpha_cutoff, Some(0.3));
+        assert!(leaf.double_sided);
+        assert_eq!(glass.blend, BlendMode::Alpha);
+    }
+
+    #[test]
+    fn sampler_filters_are_mapped() {
+        use gltf::texture::{MagFilter, MinFilter};
+        use wgpu::FilterMode::{Linear, Nearest};
+
+        assert_eq!(
+            GltfScene::mag_filter(Some(MagFilter::Nearest)),
+            Some(Nearest)
+        );
+        assert_eq!(GltfScene::mag_filter(None), None);
+        // filters without a mipmap part sample the base level only
+        assert_eq!(
+            GltfScene::min_filter(Some(MinFilter::Linear)),
+            Some((Linear, Nearest, false))
+        );
+        assert_eq!(
+            GltfScene::min_filter(Some(MinFilter::NearestMipmapLinear)),
+            Some((Nearest, Linear, true))
+        );
+        assert_eq!(GltfScene::min_filter(None), None);
+    }
+
+    #[test]
+    fn camera_without_aspect_uses_default() {
+        let mut scene = scene(vec![node("camera", vec![])]);
+        scene.nodes[0].camera = Some(0);
+        scene.cameras.push(GltfCamera {
+            projection: Projection::Perspective { fovy: 60.0 },
+            aspect: None,
+            znear: 0.1,
+            zfar: 100.0,
+        });
+        let camera = scene.camera(0, 16.0 / 9.0).unwrap().unwrap();
+        assert_eq!(camera.aspect(), 16.0 / 9.0);
+        assert_eq!(camera.eye(), Point3::new(1.0, 0.0, 0.0));
+
+        scene.cameras[0].aspect = Some(2.0);
+        assert_eq!(scene.camera(0, 16.0 / 9.0).unwrap().unwrap().aspect(), 2.0);
+        assert!(scene.camera(1, 1.0).unwrap().is_none());
+    }
+}
@@ -0,0 +1,170 @@
+//! Bine assets
+//!
+//! Author: BEKs => 18.10.2026
+//!
+//! Mesh data on the CPU as produced by the model importers, ready to
+//! be turned into vertex and index buffers
+
+use cgmath::{InnerSpace, Vector3};
+
+use crate::renderer::{MeshHandle, NormalVertex, Renderer, Vertex, VertexLayout};
+
+// === MeshData struct
+// One vertex per entry of positions. Texture coordinates and normals
+// are either empty or have the same length as positions.
+#[derive(Debug, Clone, Default)]
+pub struct MeshData {
+    pub name: String,
+    pub positions: Vec<[f32; 3]>,
+    // (0, 0) is the top left corner of the texture, as in wgpu
+    pub tex_coords: Vec<[f32; 2]>,
+    pub normals: Vec<[f32; 3]>,
+    // triangle list
+    pub indices: Vec<u32>,
+    // index into the materials of the model
+    pub material: Option<usize>,
+}
+
+impl MeshData {
+    pub fn vertex_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    // Vertices in the renderer's vertex format, missing texture
+    // coordinates are zero
+    pub fn vertices(&self) -> Vec<Vertex> {
+        self.positions
+            .iter()
+            .enumerate()
+            .map(|(i, position)| {
+                Vertex::new(
+                    *position,
+                    self.tex_coords.get(i).copied().unwrap_or_default(),
+                )
+            })
+            .collect()
+    }
+
+    // Vertices with normals for lit materials. Meshes without normals
+    // get smooth ones computed from their triangles.
+    pub fn normal_vertices(&self) -> Vec<NormalVertex> {
+        let computed;
+        let normals = if self.normals.len() == self.positions.len() {
+            &self.normals
+        } else {
+            computed = self.smooth_normals();
+            &computed
+        };
+        self.positions
+            .iter()
+            .zip(normals)
+            .enumerate()
+            .map(|(i, (position, normal))| {
+                NormalVertex::new(
+                    *position,
+                    *normal,
+                    self.tex_coords.get(i).copied().unwrap_or_default(),
+                )
+            })
+            .collect()
+    }
+
+    // Replaces the normals with smooth ones computed from the triangles
+    pub fn compute_normals(&mut self) {
+        self.normals = self.smooth_normals();
+    }
+
+    // Every vertex gets the sum of the face normals of the triangles
+    // using it, which weights larger triangles more
+    fn smooth_normals(&self) -> Vec<[f32; 3]> {
+        let mut normals = vec![Vector3::new(0.0, 0.0, 0.0); self.positions.len()];
+        for triangle in self.indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| triangle[i] as usize);
+            let (Some(&pa), Some(&pb), Some(&pc)) = (
+                self.positions.get(a),
+                self.positions.get(b),
+                self.positions.get(c),
+            ) else {
+                continue;
+            };
+            let (pa, pb, pc) = (Vector3::from(pa), Vector3::from(pb), Vector3::from(pc));
+            let normal = (pb - pa).cross(pc - pa);
+            for index in [a, b, c] {
+                normals[index] += normal;
+            }
+        }
+        normals
+            .into_iter()
+            .map(|normal| {
+                if normal.magnitude2() > 0.0 {
+                    normal.normalize().into()
+                } else {
+                    [0.0, 1.0, 0.0]
+                }
+            })
+            .collect()
+    }
+
+    // Indices narrowed to 16 bits, None when a mesh uses
+    // more vertices than 16 bit indices can address
+    pub fn indices_u16(&self) -> Option<Vec<u16>> {
+        self.indices
+            .iter()
+            .map(|&index| u16::try_from(index).ok())
+            .collect()
+    }
+
+    // Uploads the mesh to the renderer for the basic material
+    pub fn upload(&self, renderer: &mut Renderer) -> MeshHandle {
+        self.upload_vertices(renderer, &self.vertices())
+    }
+
+    // Uploads the mesh with normals, see normal_vertices()
+    pub fn upload_with_normals(&self, renderer: &mut Renderer) -> MeshHandle {
+        self.upload_vertices(renderer, &self.normal_vertices())
+    }
+
+    // 16 bit indices when they are enough, 32 bit ones otherwise
+    fn upload_vertices<V: VertexLayout>(
+        &self,
+        renderer: &mut Renderer,
+        vertices: &[V],
+    ) -> MeshHandle {
+        match self.indices_u16() {
+            Some(indices) => renderer.upload_mesh(vertices, &indices),
+            None => renderer.upload_mesh(vertices, &self.indices),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    // A strip of triangles over the given number of vertices
+    pub(crate) fn strip(vertex_count: u32) -> MeshData {
+        MeshData {
+            name: "strip".into(),
+            positions: (0..vertex_count)
+                .map(|i| [(i / 2) as f32, (i % 2) as f32, 0.0])
+                .collect(),
+            indices: (0..vertex_count - 2)
+                .flat_map(|i| [i, i + 1, i + 2])
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn small_meshes_use_16_bit_indices() {
+        let mesh = strip(65_536);
+        let indices = mesh.indices_u16().unwrap();
+        assert_eq!(indices.len(), mesh.indices.len());
+        assert_eq!(indices.last(), Some(&65_535));
+    }
+
+    #[test]
+    fn large_meshes_need_32_bit_indices() {
+        assert_eq!(strip(70_000).indices_u16(), None);
+    }
+}
@@ -0,0 +1,13 @@
+mod errors;
+pub mod gltf;
+pub mod mesh_data;
+pub mod obj;
+
+pub use self::gltf::{
+    GltfCamera, GltfMaterial, GltfMesh, GltfNode, GltfScene, GltfTexture, LoadedScene,
+    ScenePrimitive,
+};
+pub use errors::GltfError;
+pub use errors::ObjError;
+pub use mesh_data::MeshData;
+pub use obj::{ObjMaterial, ObjModel};
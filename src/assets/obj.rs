@@ -0,0 +1,228 @@
+//! Bine assets
+//!
+//! Author: BEKs => 18.10.2026
+//!
+//! Wavefront OBJ import. Every object, and every part of an object
+//! using a different material, becomes its own triangulated MeshData.
+//! Materials come from the MTL libraries the file references.
+
+use std::cell::RefCell;
+use std::io::{BufReader, Cursor};
+use std::path::{Path, PathBuf};
+
+use super::{MeshData, ObjError};
+
+// === ObjMaterial struct
+// Colors are linear rgb. Texture paths are relative to the directory
+// of the OBJ file, or as written in the file for OBJ data from bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjMaterial {
+    pub name: String,
+    pub ambient: [f32; 3],
+    pub diffuse: [f32; 3],
+    pub specular: [f32; 3],
+    pub shininess: f32,
+    // opacity, 1 is fully opaque
+    pub dissolve: f32,
+    pub diffuse_texture: Option<PathBuf>,
+    pub normal_texture: Option<PathBuf>,
+}
+
+impl ObjMaterial {
+    fn new(material: tobj::Material, base_dir: &Path) -> Self {
+        let texture = |path: Option<String>| path.map(|path| base_dir.join(path));
+        Self {
+            name: material.name,
+            ambient: material.ambient.unwrap_or([0.0; 3]),
+            diffuse: material.diffuse.unwrap_or([1.0; 3]),
+            specular: material.specular.unwrap_or([0.0; 3]),
+            shininess: material.shininess.unwrap_or(0.0),
+            dissolve: material.dissolve.unwrap_or(1.0),
+            diffuse_texture: texture(material.diffuse_texture),
+            normal_texture: texture(material.normal_texture),
+        }
+    }
+}
+
+// === ObjModel struct
+#[derive(Debug, Clone, Default)]
+pub struct ObjModel {
+    pub meshes: Vec<MeshData>,
+    // MeshData::material indexes into these
+    pub materials: Vec<ObjMaterial>,
+}
+
+impl ObjModel {
+    // Loads an OBJ file and the material libraries next to it
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ObjError> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(|err| ObjError::Io(path.to_owned(), err))?;
+        let base_dir = path.parent().unwrap_or(Path::new(""));
+        Self::parse(&bytes, base_dir, |library| {
+            tobj::load_mtl(base_dir.join(library))
+        })
+    }
+
+    // Loads OBJ data embedded in the game, e.g. with include_bytes!.
+    // The given MTL data is used for every material library the OBJ
+    // data references, without it the meshes have no materials.
+    pub fn from_bytes(obj: &[u8], mtl: Option<&[u8]>) -> Result<Self, ObjError> {
+        Self::parse(obj, Path::new(""), |_| match mtl {
+            Some(mtl) => tobj::load_mtl_buf(&mut BufReader::new(Cursor::new(mtl))),
+            None => Ok(Default::default()),
+        })
+    }
+
+    fn parse(
+        obj: &[u8],
+        base_dir: &Path,
+        load_library: impl Fn(&Path) -> tobj::MTLLoadResult,
+    ) -> Result<Self, ObjError> {
+        let options = tobj::LoadOptions {
+            single_index: true,
+            triangulate: true,
+            ignore_points: true,
+            ignore_lines: true,
+        };
+        // remember the first library that failed, tobj only
+        // reports the error itself
+        let failed_library = RefCell::new(None);
+        let (models, materials) =
+            tobj::load_obj_buf(&mut BufReader::new(Cursor::new(obj)), &options, |library| {
+                let result = load_library(library);
+                if result.is_err() {
+                    failed_library
+                        .borrow_mut()
+                        .get_or_insert_with(|| library.display().to_string());
+                }
+                result
+            })
+            .map_err(ObjError::Parse)?;
+        let materials = materials.map_err(|err| {
+            let library = failed_library.take().unwrap_or_default();
+            ObjError::Material(library, err)
+        })?;
+
+        Ok(Self {
+            meshes: models.into_iter().map(Self::mesh_data).collect(),
+            materials: materials
+                .into_iter()
+                .map(|material| ObjMaterial::new(material, base_dir))
+                .collect(),
+        })
+    }
+
+    fn mesh_data(model: tobj::Model) -> MeshData {
+        let mesh = model.mesh;
+        MeshData {
+            name: model.name,
+            positions: mesh
+                .positions
+                .chunks_exact(3)
+                .map(|p| [p[0], p[1], p[2]])
+                .collect(),
+            // OBJ puts v = 0 at the bottom of the texture
+            tex_coords: mesh
+                .texcoords
+                .chunks_exact(2)
+                .map(|uv| [uv[0], 1.0 - uv[1]])
+                .collect(),
+            normals: mesh
+                .normals
+                .chunks_exact(3)
+                .map(|n| [n[0], n[1], n[2]])
+                .collect(),
+            indices: mesh.indices,
+            material: mesh.material_id,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUAD: &str = "\
+o quad
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+vn 0 0 1
+usemtl red
+f 1/1/1 2/2/1 3/3/1 4/4/1
+";
+
+    const TRIANGLE: &str = "\
+v 0 0 0
+v 1 0 0
+v 0 1 0
+f 1 2 3
+";
+
+    const MTL: &str = "\
+newmtl red
+Kd 1 0 0
+Ns 16
+d 0.5
+map_Kd red.png
+";
+
+    #[test]
+    fn quad_with_normals_and_uvs() {
+        let model = ObjModel::from_bytes(QUAD.as_bytes(), None).unwrap();
+        assert_eq!(model.meshes.len(), 1);
+        let mesh = &model.meshes[0];
+        assert_eq!(mesh.name, "quad");
+        assert_eq!(mesh.vertex_count(), 4);
+        // triangulated into two triangles
+        assert_eq!(mesh.indices.len(), 6);
+        assert_eq!(mesh.normals, vec![[0.0, 0.0, 1.0]; 4]);
+        // v is flipped so (0, 0) is the top left corner
+        let corner = mesh
+            .positions
+            .iter()
+            .position(|p| *p == [0.0, 0.0, 0.0])
+            .unwrap();
+        assert_eq!(mesh.tex_coords[corner], [0.0, 1.0]);
+        // no library data, so no materials
+        assert!(model.materials.is_empty());
+    }
+
+    #[test]
+    fn triangle_without_normals_and_uvs() {
+        let model = ObjModel::from_bytes(TRIANGLE.as_bytes(), None).unwrap();
+        let mesh = &model.meshes[0];
+        assert_eq!(mesh.vertex_count(), 3);
+        assert_eq!(mesh.indices, [0, 1, 2]);
+        assert!(mesh.normals.is_empty());
+        assert!(mesh.tex_coords.is_empty());
+        assert_eq!(mesh.material, None);
+        // missing attributes are filled in for upload
+        assert_eq!(mesh.vertices().len(), 3);
+        assert_eq!(mesh.normal_vertices().len(), 3);
+    }
+
+    #[test]
+    fn materials_come_from_mtl_data() {
+        let obj = format!("mtllib quad.mtl\n{QUAD}");
+        let model = ObjModel::from_bytes(obj.as_bytes(), Some(MTL.as_bytes())).unwrap();
+        assert_eq!(model.materials.len(), 1);
+        let material = &model.materials[0];
+        assert_eq!(material.name, "red");
+        assert_eq!(material.diffuse, [1.0, 0.0, 0.0]);
+        assert_eq!(material.shininess, 16.0);
+        assert_eq!(material.dissolve, 0.5);
+        assert_eq!(material.diffuse_texture, Some(PathBuf::from("red.png")));
+        assert_eq!(model.meshes[0].material, Some(0));
+    }
+
+    #[test]
+    fn broken_obj_is_an_error() {
+        assert!(ObjModel::from_bytes(b"f 1 2 3\n", None).is_err());
+    }
+}
@@ -16,7 +16,7 @@ use winit::{
 use crate::input::Input;
 
 use super::super::{
-    renderer::{Renderer, RendererBackends},
+    renderer::{RenderError, Renderer, RendererBackends, RendererBuilder},
     window::{Window, WindowConfig},
 };
 
@@ -29,10 +29,26 @@ pub trait Game {
     /**
      * This is where the game will load textures and other initial setup
      * for the game. This will always be called after window and renderer
-     * are created.
+     * are created. It is called once, a renderer created again after
+     * a device loss goes to on_renderer_recreated instead.
      */
     fn on_init(&mut self, renderer: &mut Renderer);
 
+    /**
+     * This is called after the device was lost and the renderer had to be
+     * created again. Every mesh, texture, material and font handle of the
+     * old renderer is gone and has to be uploaded again. Present mode,
+     * sample count, depth config, camera, lights and shader hot reload
+     * are carried over, light handles stay valid.
+     *
+     * The default calls on_init again, games that do one-time setup
+     * there (spawning entities, loading saves) should override this and
+     * only recreate their GPU resources.
+     */
+    fn on_renderer_recreated(&mut self, renderer: &mut Renderer) {
+        self.on_init(renderer);
+    }
+
     /**
      * This receives the delta time and a reference to input so the game will
      * read the input keys and update the game state. All game state processing is
@@ -42,9 +58,19 @@ pub trait Game {
 
     /**
      * This is called on each frame with a reference to the renderer so the game
-     * can issue draw calls.
+     * can issue draw calls. Errors returned from Renderer::render should be
+     * passed on so the engine can shut down or rebuild the renderer.
      */
-    fn on_draw(&mut self, renderer: &mut Renderer);
+    fn on_draw(&mut self, renderer: &mut Renderer) -> Result<(), RenderError>;
+
+    /**
+     * This is called after the window was resized and the renderer has
+     * been reconfigured for the new size, which is given in physical pixels.
+     * Cameras handed to the renderer already pick up the new aspect ratio,
+     * so this is for anything else that depends on the size, like a Camera2D
+     * viewport or UI layout.
+     */
+    fn on_resize(&mut self, _renderer: &mut Renderer, _width: u32, _height: u32) {}
 }
 
 // === Engine struct
@@ -58,7 +84,7 @@ pub struct Engine<G: Game> {
     // config settings for window
     window_config: WindowConfig,
     // renderer specific config
-    backend: RendererBackends,
+    renderer_builder: RendererBuilder,
 
     // input device
     input: Input,
@@ -77,19 +103,32 @@ impl<G: Game> Engine<G> {
     const TIME_STEP: f32 = 1.0 / 60.0; // how often should I update game logic
 
     pub fn new(window_config: WindowConfig, backend: RendererBackends, game: G) -> Self {
+        let renderer_builder = RendererBuilder::new()
+            .backend(backend)
+            .vsync(window_config.vsync);
+        Self::with_renderer_builder(window_config, renderer_builder, game)
+    }
+
+    // Creates the renderer from the given builder instead of the defaults,
+    // the builder settings take precedence over the window config vsync
+    pub fn with_renderer_builder(
+        window_config: WindowConfig,
+        renderer_builder: RendererBuilder,
+        game: G,
+    ) -> Self {
         Self {
             window: None,
             renderer: None,
-            game: game,
-            window_config: window_config,
+            game,
+            window_config,
             last_update: None,
             accumulator: 0.0,
-            backend: backend,
+            renderer_builder,
             input: Input::new(),
         }
     }
 
-    fn run_game_loop(&mut self) {
+    fn run_game_loop(&mut self, event_loop: &ActiveEventLoop) {
         // calculate delta time as time since last frame processing began
         let dt = Instant::now();
         let frame_time = if let Some(last_update) = self.last_update {
@@ -115,7 +154,7 @@ impl<G: Game> Engine<G> {
         self.last_update = Some(dt);
         self.input.update();
 
-        self.render();
+        self.render(event_loop);
     }
 
     // update game logic and time changes
@@ -124,16 +163,65 @@ impl<G: Game> Engine<G> {
     }
 
     // render UI and other sprites in game
-    fn render(&mut self) {
-        if let Some(renderer) = self.renderer.as_mut() {
-            self.game.on_draw(renderer);
-            // renderer.clear(120.0, 250.0, 88.0);
+    fn render(&mut self, event_loop: &ActiveEventLoop) {
+        let Some(renderer) = self.renderer.as_mut() else {
+            return;
+        };
+        match self.game.on_draw(renderer) {
+            Ok(()) => {}
+            Err(RenderError::OutOfMemory) => {
+                log::error!("Renderer ran out of memory, shutting down");
+                event_loop.exit();
+            }
+            Err(RenderError::DeviceLost(e)) => {
+                // everything uploaded to the old device is gone, the
+                // game uploads it again to the new renderer
+                log::warn!("Device lost ({e}), recreating renderer");
+                let settings = self.renderer.take().map(Renderer::into_runtime_settings);
+                self.create_renderer();
+                match self.renderer.as_mut() {
+                    Some(renderer) => {
+                        if let Some(settings) = settings {
+                            renderer.apply_runtime_settings(settings);
+                        }
+                        self.game.on_renderer_recreated(renderer);
+                    }
+                    None => event_loop.exit(),
+                }
+            }
+            Err(e) => log::error!("Failed to render frame: {e}"),
         }
     }
 
-    // handle window resizing changes using window module
+    // create the renderer for the current window
+    fn create_renderer(&mut self) {
+        if let Some(window) = &self.window {
+            let builder = self.renderer_builder.clone();
+            self.renderer = match pollster::block_on(builder.build(window.inner())) {
+                Ok(r) => Some(r),
+                Err(e) => {
+                    log::error!("Failed to create valid renderer: {e:#}");
+                    None
+                }
+            };
+        }
+    }
 
-    fn handle_resizing() {}
+    // handle window resizing changes using window module
+    fn handle_resizing(&mut self, width: u32, height: u32) {
+        if let Some(window) = self.window.as_mut() {
+            window.resized(width, height);
+        }
+        // a minimized window reports a zero size, there is
+        // nothing to reconfigure until it is restored
+        if width == 0 || height == 0 {
+            return;
+        }
+        if let Some(renderer) = self.renderer.as_mut() {
+            renderer.resize(width, height);
+            self.game.on_resize(renderer, width, height);
+        }
+    }
 }
 
 // === winit ApplicationHandler for Engine
@@ -142,25 +230,16 @@ impl<G: Game> ApplicationHandler for Engine<G> {
         // create window
         match Window::create(&self.window_config, event_loop) {
             Ok(window) => {
-                println!("Window created successfully!");
+                log::info!("Window created successfully!");
                 self.window = Some(window);
             }
             Err(e) => {
-                eprintln!("Failed to create window: {:?}", e);
+                log::error!("Failed to create window: {:?}", e);
                 event_loop.exit();
             }
         }
         // create renderer
-        if let Some(window) = &self.window {
-            self.renderer = pollster::block_on(async {
-                if let Ok(r) = Renderer::new(window.inner(), self.backend.clone()).await {
-                    Some(r)
-                } else {
-                    println!("Failed to create valid renderer");
-                    None
-                }
-            });
-        }
+        self.create_renderer();
 
         self.last_update = Some(Instant::now());
 
@@ -180,12 +259,20 @@ impl<G: Game> ApplicationHandler for Engine<G> {
             WindowEvent::CloseRequested => {
                 event_loop.exit();
             }
+            WindowEvent::Resized(size) => self.handle_resizing(size.width, size.height),
+
+            WindowEvent::ScaleFactorChanged { .. } => {
+                if let Some(size) = self.window.as_ref().map(|w| w.inner().inner_size()) {
+                    self.handle_resizing(size.width, size.height);
+                }
+            }
+
             WindowEvent::KeyboardInput {
                 event: key_event, ..
             } => self.input.handle_keyboard_event(&key_event),
 
             WindowEvent::RedrawRequested => {
-                self.run_game_loop();
+                self.run_game_loop(event_loop);
 
                 self.window.as_ref().unwrap().request_redraw();
             }
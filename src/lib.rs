@@ -2,6 +2,7 @@
 //!
 //! Author: BEKs => 04.11.2025
 //!
+pub mod assets;
 pub mod core;
 pub mod input;
 pub mod renderer;
@@ -1,10 +1,13 @@
 use bine::{
     core::{Engine, Game},
-    renderer::{Camera, Renderer, RendererBackends, Vertex},
+    renderer::{
+        BlendMode, Camera, Instance, MaterialHandle, MeshHandle, RenderError, Renderer,
+        RendererBackends, TextureHandle, Vertex,
+    },
     window::WindowConfig,
 };
 
-use cgmath::{self, InnerSpace, Point3, Rad, Vector3, num_traits::Float};
+use cgmath::{self, InnerSpace, Point3, Vector3, num_traits::Float};
 use winit::event_loop::{ControlFlow, EventLoop};
 
 const WINDOW_WIDTH: u32 = 1980;
@@ -13,13 +16,23 @@ const ASPECT_RATIO: f32 = (WINDOW_WIDTH / WINDOW_HEIGHT) as f32;
 
 struct DemoGame {
     camera_controller: CameraController,
+    mesh: Option<MeshHandle>,
+    texture: Option<TextureHandle>,
+    material: Option<MaterialHandle>,
 }
 impl Game for DemoGame {
     fn on_init(&mut self, renderer: &mut Renderer) {
-        renderer.set_geometry(Self::VERTICES, Self::INDICES);
+        // pick up shader edits without rebuilding while developing
+        if cfg!(debug_assertions) {
+            renderer.enable_shader_hot_reload(concat!(env!("CARGO_MANIFEST_DIR"), "/shaders"));
+        }
+
+        self.mesh = Some(renderer.upload_mesh(Self::VERTICES, Self::INDICES));
 
         let bytes = include_bytes!("../assets/textures/happy-tree.png");
-        renderer.load_texture(bytes, "happy-tree");
+        self.texture = renderer.load_texture(bytes, "happy-tree").ok();
+        // the tree texture has transparent parts
+        self.material = Some(renderer.basic_material(BlendMode::Alpha));
 
         renderer.set_camera(
             (0.0, 1.0, 2.0).into(),
@@ -41,9 +54,14 @@ impl Game for DemoGame {
         }
     }
 
-    fn on_draw(&mut self, renderer: &mut Renderer) {
+    fn on_draw(&mut self, renderer: &mut Renderer) -> Result<(), RenderError> {
         renderer.update_camera(&self.camera_controller.to_camera());
-        renderer.render(120.0, 250.0, 88.0);
+        if let (Some(mesh), Some(texture), Some(material)) =
+            (self.mesh, self.texture, self.material)
+        {
+            renderer.draw_with_material(material, mesh, texture, &[Instance::default()]);
+        }
+        renderer.render(120.0, 250.0, 88.0)
     }
 }
 
@@ -73,6 +91,9 @@ impl DemoGame {
                 Vector3::new(0.0, 1.0, 2.0).magnitude(),
                 (0.0, 0.0, 0.0),
             ),
+            mesh: None,
+            texture: None,
+            material: None,
         }
     }
 }
@@ -148,6 +169,6 @@ fn main() {
         fullscreen: false,
     };
     let game = DemoGame::new();
-    let mut engine = Engine::new(config, RendererBackends::Metal, game);
+    let mut engine = Engine::new(config, RendererBackends::Auto, game);
     let _ = event_loop.run_app(&mut engine);
 }
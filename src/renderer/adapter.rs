@@ -0,0 +1,179 @@
+//! Bine renderer
+//!
+//! Author: BEKs => 18.10.2026
+//!
+//! Adapter (GPU) selection. The backends of a RendererBackends value are
+//! tried in order of preference and the software fallback adapter is the
+//! last resort. Every failed attempt is recorded for the final error.
+
+use anyhow::{Context, Result, bail};
+use wgpu::{
+    Backend, Backends, InstanceDescriptor, PowerPreference, RequestAdapterOptions,
+    SurfaceTargetUnsafe,
+};
+use winit::window::Window;
+
+use super::{RendererBackends, errors::AdapterNotFound};
+
+// === AdapterDetails struct
+// An adapter found by Renderer::enumerate_adapters. Pass it to
+// Renderer::with_adapter or RendererBuilder::adapter to render on that GPU.
+#[derive(Debug, Clone)]
+pub struct AdapterDetails {
+    pub name: String,
+    pub backend: Backend,
+    pub device_type: wgpu::DeviceType,
+    pub driver: String,
+    pub vendor: u32,
+    pub device: u32,
+    pub limits: wgpu::Limits,
+}
+
+impl AdapterDetails {
+    pub(crate) fn new(adapter: &wgpu::Adapter) -> Self {
+        let info = adapter.get_info();
+        Self {
+            name: info.name,
+            backend: info.backend,
+            device_type: info.device_type,
+            driver: info.driver,
+            vendor: info.vendor,
+            device: info.device,
+            limits: adapter.limits(),
+        }
+    }
+
+    fn matches(&self, adapter: &wgpu::Adapter) -> bool {
+        let info = adapter.get_info();
+        info.backend == self.backend
+            && info.vendor == self.vendor
+            && info.device == self.device
+            && info.name == self.name
+    }
+}
+
+// === SelectedAdapter struct
+// The adapter together with the instance (and surface) it was found on
+pub(crate) struct SelectedAdapter {
+    pub(crate) instance: wgpu::Instance,
+    pub(crate) surface: Option<wgpu::Surface<'static>>,
+    pub(crate) adapter: wgpu::Adapter,
+}
+
+fn create_instance(backends: Backends) -> wgpu::Instance {
+    wgpu::Instance::new(&InstanceDescriptor {
+        backends,
+        ..Default::default()
+    })
+}
+
+fn create_surface(instance: &wgpu::Instance, window: &Window) -> Result<wgpu::Surface<'static>> {
+    // ===
+    // This hack is to be investigated later
+    unsafe {
+        let target = SurfaceTargetUnsafe::from_window(window)
+            .context("Failed to get the raw window handles")?;
+        instance
+            .create_surface_unsafe(target)
+            .context("Failed to create surface")
+    }
+}
+
+// Tries the preferred backends one after another, then the software
+// fallback adapter on any of them
+pub(crate) async fn select_adapter(
+    backend: RendererBackends,
+    power_preference: PowerPreference,
+    window: Option<&Window>,
+) -> Result<SelectedAdapter> {
+    let candidates = backend.candidates();
+    let mut tried = Vec::new();
+
+    for candidate in &candidates {
+        match request_adapter(Backends::from(*candidate), power_preference, false, window).await {
+            Ok(selected) => return Ok(selected),
+            Err(e) => tried.push(format!("{candidate}: {e:#}")),
+        }
+    }
+
+    let all = candidates.iter().fold(Backends::empty(), |all, candidate| {
+        all | (*candidate).into()
+    });
+    match request_adapter(all, power_preference, true, window).await {
+        Ok(selected) => Ok(selected),
+        Err(e) => {
+            tried.push(format!("software fallback: {e:#}"));
+            Err(AdapterNotFound { tried }.into())
+        }
+    }
+}
+
+async fn request_adapter(
+    backends: Backends,
+    power_preference: PowerPreference,
+    force_fallback_adapter: bool,
+    window: Option<&Window>,
+) -> Result<SelectedAdapter> {
+    let instance = create_instance(backends);
+    let surface = window
+        .map(|window| create_surface(&instance, window))
+        .transpose()?;
+    let adapter = instance
+        .request_adapter(&RequestAdapterOptions {
+            power_preference,
+            compatible_surface: surface.as_ref(),
+            force_fallback_adapter,
+        })
+        .await?;
+
+    Ok(SelectedAdapter {
+        instance,
+        surface,
+        adapter,
+    })
+}
+
+// Finds an adapter returned by enumerate_adapters() again
+pub(crate) fn open_adapter(
+    details: &AdapterDetails,
+    window: Option<&Window>,
+) -> Result<SelectedAdapter> {
+    let instance = create_instance(details.backend.into());
+    let adapter = instance
+        .enumerate_adapters(details.backend.into())
+        .into_iter()
+        .find(|adapter| details.matches(adapter))
+        .with_context(|| {
+            format!(
+                "Adapter {} ({}) is no longer available",
+                details.name, details.backend
+            )
+        })?;
+
+    let surface = window
+        .map(|window| create_surface(&instance, window))
+        .transpose()?;
+    if let Some(surface) = &surface
+        && !adapter.is_surface_supported(surface)
+    {
+        bail!("Adapter {} can not present to this window", details.name);
+    }
+
+    Ok(SelectedAdapter {
+        instance,
+        surface,
+        adapter,
+    })
+}
+
+pub(crate) fn enumerate_adapters(backend: RendererBackends) -> Vec<AdapterDetails> {
+    let backends = backend
+        .candidates()
+        .into_iter()
+        .fold(Backends::empty(), |all, candidate| all | candidate.into());
+    create_instance(backends)
+        .enumerate_adapters(backends)
+        .iter()
+        .map(AdapterDetails::new)
+        .collect()
+}
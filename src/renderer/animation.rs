@@ -0,0 +1,279 @@
+//! Bine renderer
+//!
+//! Author: BEKs => 18.10.2026
+//!
+//! Sprite sheet animation. A SpriteAnimation steps through a list of
+//! uv rects, advanced by the dt the game gets in on_update, and hands
+//! out the rect of the current frame to draw the sprite with.
+
+use anyhow::{Context, Result, bail};
+
+use super::{Rect, TextureAtlas};
+
+// === PlaybackMode enum
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlaybackMode {
+    // stops on the last frame
+    Once,
+    #[default]
+    Loop,
+    // plays forward, then backward, and so on
+    PingPong,
+}
+
+// === AnimationFrame struct
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnimationFrame {
+    pub region: Rect,
+    // seconds the frame is shown
+    pub duration: f32,
+}
+
+impl AnimationFrame {
+    pub fn new(region: Rect, duration: f32) -> Self {
+        Self { region, duration }
+    }
+}
+
+// === SpriteAnimation struct
+#[derive(Debug, Clone)]
+pub struct SpriteAnimation {
+    frames: Vec<AnimationFrame>,
+    mode: PlaybackMode,
+    // index of the current frame and time spent on it
+    current: usize,
+    elapsed: f32,
+    // playing backward in ping pong mode
+    reversed: bool,
+    finished: bool,
+    // seconds until a looping animation is back in the same state
+    cycle: f32,
+}
+
+impl SpriteAnimation {
+    pub fn new(frames: Vec<AnimationFrame>, mode: PlaybackMode) -> Result<Self> {
+        if frames.is_empty() {
+            bail!("Animation needs at least one frame");
+        }
+        if let Some(frame) = frames
+            .iter()
+            .find(|frame| !frame.duration.is_finite() || frame.duration <= 0.0)
+        {
+            bail!(
+                "Animation frame durations must be positive and finite, got {}",
+                frame.duration
+            );
+        }
+        let total: f32 = frames.iter().map(|frame| frame.duration).sum();
+        // ping pong shows every frame but the first and last twice
+        let cycle = match mode {
+            PlaybackMode::PingPong if frames.len() > 2 => {
+                total
+                    + frames[1..frames.len() - 1]
+                        .iter()
+                        .map(|frame| frame.duration)
+                        .sum::<f32>()
+            }
+            _ => total,
+        };
+        Ok(Self {
+            frames,
+            mode,
+            current: 0,
+            elapsed: 0.0,
+            reversed: false,
+            finished: false,
+            cycle,
+        })
+    }
+
+    // Every frame is shown for the same duration
+    pub fn from_regions(
+        regions: impl IntoIterator<Item = Rect>,
+        frame_duration: f32,
+        mode: PlaybackMode,
+    ) -> Result<Self> {
+        let frames = regions
+            .into_iter()
+            .map(|region| AnimationFrame::new(region, frame_duration))
+            .collect();
+        Self::new(frames, mode)
+    }
+
+    // Frames are the atlas images with the given names, in order
+    pub fn from_atlas(
+        atlas: &TextureAtlas,
+        names: &[&str],
+        frame_duration: f32,
+        mode: PlaybackMode,
+    ) -> Result<Self> {
+        let regions = names
+            .iter()
+            .map(|name| {
+                atlas
+                    .region(name)
+                    .with_context(|| format!("Atlas has no image {name}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Self::from_regions(regions, frame_duration, mode)
+    }
+
+    // Advances the animation by dt seconds, skipping frames
+    // when dt is longer than the current one
+    pub fn update(&mut self, dt: f32) {
+        if self.finished || dt.is_nan() || dt <= 0.0 {
+            return;
+        }
+        // an infinite dt finishes Once animations, looping ones
+        // end up somewhere in their cycle
+        self.elapsed += dt.min(f32::MAX);
+        // whole cycles change nothing, skip them so huge steps
+        // do not walk through every frame
+        if self.mode != PlaybackMode::Once && self.elapsed >= self.cycle {
+            self.elapsed %= self.cycle;
+        }
+        while !self.finished && self.elapsed >= self.frames[self.current].duration {
+            self.elapsed -= self.frames[self.current].duration;
+            self.advance();
+        }
+    }
+
+    fn advance(&mut self) {
+        let last = self.frames.len() - 1;
+        match self.mode {
+            PlaybackMode::Once if self.current == last => {
+                self.finished = true;
+                self.elapsed = 0.0;
+            }
+            PlaybackMode::Once => self.current += 1,
+            PlaybackMode::Loop => self.current = (self.current + 1) % self.frames.len(),
+            PlaybackMode::PingPong if last == 0 => {}
+            PlaybackMode::PingPong => {
+                if self.reversed && self.current == 0 {
+                    self.reversed = false;
+                } else if !self.reversed && self.current == last {
+                    self.reversed = true;
+                }
+                if self.reversed {
+                    self.current -= 1;
+                } else {
+                    self.current += 1;
+                }
+            }
+        }
+    }
+
+    // Uv rect to draw the sprite with this frame
+    pub fn current_frame(&self) -> Rect {
+        self.frames[self.current].region
+    }
+
+    pub fn frame_index(&self) -> usize {
+        self.current
+    }
+
+    pub fn frames(&self) -> &[AnimationFrame] {
+        &self.frames
+    }
+
+    pub fn mode(&self) -> PlaybackMode {
+        self.mode
+    }
+
+    // Only Once animations finish, on their last frame
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    // Starts again from the first frame
+    pub fn reset(&mut self) {
+        self.current = 0;
+        self.elapsed = 0.0;
+        self.reversed = false;
+        self.finished = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(index: usize) -> Rect {
+        Rect::new(index as f32 * 0.25, 0.0, 0.25, 1.0)
+    }
+
+    fn frames_of(count: usize, mode: PlaybackMode) -> SpriteAnimation {
+        SpriteAnimation::from_regions((0..count).map(region), 0.1, mode).unwrap()
+    }
+
+    #[test]
+    fn loop_wraps_to_first_frame() {
+        let mut animation = frames_of(3, PlaybackMode::Loop);
+        let mut frames = Vec::new();
+        for _ in 0..7 {
+            frames.push(animation.frame_index());
+            animation.update(0.1);
+        }
+        assert_eq!(frames, [0, 1, 2, 0, 1, 2, 0]);
+        assert!(!animation.is_finished());
+    }
+
+    #[test]
+    fn ping_pong_turns_at_both_ends() {
+        let mut animation = frames_of(3, PlaybackMode::PingPong);
+        let mut frames = Vec::new();
+        for _ in 0..7 {
+            frames.push(animation.frame_index());
+            animation.update(0.1);
+        }
+        assert_eq!(frames, [0, 1, 2, 1, 0, 1, 2]);
+    }
+
+    #[test]
+    fn once_finishes_on_last_frame() {
+        let mut animation = frames_of(3, PlaybackMode::Once);
+        animation.update(0.25);
+        assert_eq!(animation.frame_index(), 2);
+        assert!(!animation.is_finished());
+        animation.update(0.1);
+        assert_eq!(animation.frame_index(), 2);
+        assert!(animation.is_finished());
+        assert_eq!(animation.current_frame(), region(2));
+    }
+
+    #[test]
+    fn large_dt_keeps_position_in_cycle() {
+        let mut animation = frames_of(4, PlaybackMode::Loop);
+        // 1000 whole cycles and one and a half frames
+        animation.update(1000.0 * 0.4 + 0.15);
+        assert_eq!(animation.frame_index(), 1);
+
+        let mut ping_pong = frames_of(4, PlaybackMode::PingPong);
+        ping_pong.update(1.0e30);
+        assert!(ping_pong.frame_index() < 4);
+    }
+
+    #[test]
+    fn infinite_dt_terminates() {
+        let mut looping = frames_of(4, PlaybackMode::Loop);
+        looping.update(f32::INFINITY);
+        assert!(looping.frame_index() < 4);
+        looping.update(0.1);
+
+        let mut once = frames_of(4, PlaybackMode::Once);
+        once.update(f32::INFINITY);
+        assert!(once.is_finished());
+        assert_eq!(once.frame_index(), 3);
+    }
+
+    #[test]
+    fn invalid_durations_are_rejected() {
+        for duration in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+            assert!(
+                SpriteAnimation::from_regions([region(0)], duration, PlaybackMode::Loop).is_err(),
+                "{duration}"
+            );
+        }
+        assert!(SpriteAnimation::new(Vec::new(), PlaybackMode::Loop).is_err());
+    }
+}
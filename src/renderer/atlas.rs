@@ -0,0 +1,265 @@
+//! Bine renderer
+//!
+//! Author: BEKs => 18.10.2026
+//!
+//! Runtime texture atlas. Many small images are packed into one texture
+//! with a shelf packer, tallest images first, and every image gets a
+//! named uv rectangle to draw it with.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result, bail};
+use image::{GenericImage, RgbaImage};
+
+use super::{Rect, TextureHandle};
+
+// === AtlasBuilder struct
+// Collects the images of an atlas. Pass it to Renderer::create_atlas
+// to pack and upload them.
+#[derive(Debug, Clone)]
+pub struct AtlasBuilder {
+    images: Vec<(String, RgbaImage)>,
+    padding: u32,
+    max_size: u32,
+}
+
+impl Default for AtlasBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AtlasBuilder {
+    pub fn new() -> Self {
+        Self {
+            images: Vec::new(),
+            padding: 1,
+            max_size: 4096,
+        }
+    }
+
+    // Transparent pixels left between images so filtering
+    // does not bleed neighbours into each other
+    pub fn with_padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    // Largest width and height the atlas texture may grow to
+    pub fn with_max_size(mut self, max_size: u32) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    pub fn add_image(&mut self, name: &str, image: RgbaImage) -> &mut Self {
+        self.images.push((name.into(), image));
+        self
+    }
+
+    // Decodes an encoded image (png, jpeg, ...) and adds it
+    pub fn add_bytes(&mut self, name: &str, bytes: &[u8]) -> Result<&mut Self> {
+        let image = image::load_from_memory(bytes)
+            .with_context(|| format!("Failed to decode atlas image {name}"))?;
+        Ok(self.add_image(name, image.to_rgba8()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.images.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.images.is_empty()
+    }
+
+    // Packs the images into the smallest power of two square (or 2:1)
+    // image they fit in and returns it with the uv rect of every image
+    pub(crate) fn pack(&self) -> Result<(RgbaImage, HashMap<String, Rect>)> {
+        if self.images.is_empty() {
+            bail!("Atlas has no images");
+        }
+
+        let mut order: Vec<usize> = (0..self.images.len()).collect();
+        order.sort_by_key(|&i| {
+            let (width, height) = self.images[i].1.dimensions();
+            (std::cmp::Reverse(height), std::cmp::Reverse(width))
+        });
+
+        let area: u64 = self
+            .images
+            .iter()
+            .map(|(_, image)| {
+                (image.width() + self.padding) as u64 * (image.height() + self.padding) as u64
+            })
+            .sum();
+        let mut width = (area as f64).sqrt().ceil().max(1.0) as u32;
+        width = width.next_power_of_two();
+        let mut height = width / 2;
+
+        let positions = loop {
+            if height.max(1) > self.max_size || width > self.max_size {
+                bail!(
+                    "{} images do not fit into a {}x{} atlas",
+                    self.images.len(),
+                    self.max_size,
+                    self.max_size
+                );
+            }
+            if let Some(positions) = self.place(&order, width, height.max(1)) {
+                break positions;
+            }
+            // grow the shorter side
+            if height < width {
+                height = width;
+            } else {
+                width *= 2;
+            }
+        };
+        let height = height.max(1);
+
+        let mut atlas = RgbaImage::new(width, height);
+        let mut regions = HashMap::with_capacity(self.images.len());
+        for (&i, &(x, y)) in order.iter().zip(&positions) {
+            let (name, image) = &self.images[i];
+            atlas.copy_from(image, x, y)?;
+            regions.insert(
+                name.clone(),
+                Rect::new(
+                    x as f32 / width as f32,
+                    y as f32 / height as f32,
+                    image.width() as f32 / width as f32,
+                    image.height() as f32 / height as f32,
+                ),
+            );
+        }
+        Ok((atlas, regions))
+    }
+
+    // Places the images in rows (shelves) as tall as their first image.
+    // Returns the top left corner of every image, in the given order.
+    fn place(&self, order: &[usize], width: u32, height: u32) -> Option<Vec<(u32, u32)>> {
+        let mut positions = Vec::with_capacity(order.len());
+        let (mut x, mut y, mut shelf_height) = (0, 0, 0);
+        for &i in order {
+            let (image_width, image_height) = self.images[i].1.dimensions();
+            if image_width > width {
+                return None;
+            }
+            if x + image_width > width {
+                x = 0;
+                y += shelf_height + self.padding;
+                shelf_height = 0;
+            }
+            if y + image_height > height {
+                return None;
+            }
+            positions.push((x, y));
+            x += image_width + self.padding;
+            shelf_height = shelf_height.max(image_height);
+        }
+        Some(positions)
+    }
+}
+
+// === TextureAtlas struct
+// A packed atlas uploaded to the GPU. Draw its images with the atlas
+// texture and the uv rect of the image, e.g. in a SpriteBatch.
+#[derive(Debug, Clone)]
+pub struct TextureAtlas {
+    pub(crate) texture: TextureHandle,
+    pub(crate) regions: HashMap<String, Rect>,
+    pub(crate) size: (u32, u32),
+}
+
+impl TextureAtlas {
+    pub fn texture(&self) -> TextureHandle {
+        self.texture
+    }
+
+    pub fn region(&self, name: &str) -> Option<Rect> {
+        self.regions.get(name).copied()
+    }
+
+    pub fn regions(&self) -> impl Iterator<Item = (&str, Rect)> {
+        self.regions
+            .iter()
+            .map(|(name, rect)| (name.as_str(), *rect))
+    }
+
+    // Size of the atlas texture in pixels
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::Rgba;
+
+    use super::*;
+
+    fn solid(width: u32, height: u32, value: u8) -> RgbaImage {
+        RgbaImage::from_pixel(width, height, Rgba([value, value, value, 255]))
+    }
+
+    // Pixel rectangle of a uv rect in the packed image
+    fn pixels(rect: Rect, size: (u32, u32)) -> (u32, u32, u32, u32) {
+        (
+            (rect.x * size.0 as f32).round() as u32,
+            (rect.y * size.1 as f32).round() as u32,
+            (rect.width * size.0 as f32).round() as u32,
+            (rect.height * size.1 as f32).round() as u32,
+        )
+    }
+
+    #[test]
+    fn images_do_not_overlap_and_keep_their_pixels() {
+        let mut builder = AtlasBuilder::new().with_padding(2);
+        let sizes = [(16, 16), (30, 8), (5, 20), (12, 12), (40, 3), (1, 1)];
+        for (i, &(width, height)) in sizes.iter().enumerate() {
+            builder.add_image(&format!("image{i}"), solid(width, height, 10 + i as u8));
+        }
+        let (atlas, regions) = builder.pack().unwrap();
+        let size = atlas.dimensions();
+        assert!(size.0.is_power_of_two() && size.1.is_power_of_two());
+        assert_eq!(regions.len(), sizes.len());
+
+        let rects: Vec<_> = (0..sizes.len())
+            .map(|i| pixels(regions[&format!("image{i}")], size))
+            .collect();
+        for (i, &(x, y, width, height)) in rects.iter().enumerate() {
+            assert_eq!((width, height), sizes[i]);
+            assert!(x + width <= size.0 && y + height <= size.1);
+            assert_eq!(atlas.get_pixel(x, y).0[0], 10 + i as u8);
+            assert_eq!(
+                atlas.get_pixel(x + width - 1, y + height - 1).0[0],
+                10 + i as u8
+            );
+            for &(ox, oy, other_width, other_height) in &rects[i + 1..] {
+                let apart = x + width + 2 <= ox
+                    || ox + other_width + 2 <= x
+                    || y + height + 2 <= oy
+                    || oy + other_height + 2 <= y;
+                assert!(apart, "image{i} overlaps or touches a neighbour");
+            }
+        }
+    }
+
+    #[test]
+    fn atlas_grows_past_first_guess() {
+        let mut builder = AtlasBuilder::new().with_padding(0);
+        // far too wide for the square the area suggests
+        builder.add_image("wide", solid(100, 1, 1));
+        builder.add_image("small", solid(4, 4, 2));
+        let (atlas, regions) = builder.pack().unwrap();
+        assert!(atlas.width() >= 128);
+        assert_eq!(regions.len(), 2);
+    }
+
+    #[test]
+    fn too_large_images_fail() {
+        let mut builder = AtlasBuilder::new().with_max_size(64);
+        builder.add_image("big", solid(65, 10, 1));
+        assert!(builder.pack().is_err());
+        assert!(AtlasBuilder::new().pack().is_err());
+    }
+}
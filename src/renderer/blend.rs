@@ -0,0 +1,63 @@
+//! Bine renderer
+//!
+//! Author: BEKs => 18.10.2026
+//!
+//! Blend modes a material can be drawn with
+
+use wgpu::{BlendComponent, BlendFactor, BlendOperation, BlendState};
+
+// === BlendMode enum
+// How the fragments of a material are combined with what is already
+// in the frame. Everything except Opaque is transparent: it is drawn
+// after the opaque draws, sorted back to front, without depth writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BlendMode {
+    #[default]
+    Opaque,
+    // straight (non premultiplied) alpha
+    Alpha,
+    // colors already multiplied with their alpha
+    Premultiplied,
+    // adds the color weighted by alpha, for glows and particles
+    Additive,
+    // multiplies the frame with the color, for shadows and tinting
+    Multiply,
+}
+
+impl BlendMode {
+    pub fn is_transparent(&self) -> bool {
+        *self != BlendMode::Opaque
+    }
+
+    pub(crate) fn blend_state(&self) -> BlendState {
+        match self {
+            BlendMode::Opaque => BlendState::REPLACE,
+            BlendMode::Alpha => BlendState::ALPHA_BLENDING,
+            BlendMode::Premultiplied => BlendState::PREMULTIPLIED_ALPHA_BLENDING,
+            BlendMode::Additive => BlendState {
+                color: BlendComponent {
+                    src_factor: BlendFactor::SrcAlpha,
+                    dst_factor: BlendFactor::One,
+                    operation: BlendOperation::Add,
+                },
+                alpha: BlendComponent {
+                    src_factor: BlendFactor::Zero,
+                    dst_factor: BlendFactor::One,
+                    operation: BlendOperation::Add,
+                },
+            },
+            BlendMode::Multiply => BlendState {
+                color: BlendComponent {
+                    src_factor: BlendFactor::Dst,
+                    dst_factor: BlendFactor::Zero,
+                    operation: BlendOperation::Add,
+                },
+                alpha: BlendComponent {
+                    src_factor: BlendFactor::Zero,
+                    dst_factor: BlendFactor::One,
+                    operation: BlendOperation::Add,
+                },
+            },
+        }
+    }
+}
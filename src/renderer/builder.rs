@@ -0,0 +1,267 @@
+//! Bine renderer
+//!
+//! Author: BEKs => 18.10.2026
+//!
+//! RendererBuilder configures everything that is fixed once the device
+//! and surface exist: backend and adapter, device features and limits,
+//! MSAA sample count, depth testing, surface format, alpha mode and the
+//! initial present mode.
+
+use anyhow::{Context, Result, bail};
+use wgpu::{
+    CompositeAlphaMode, DeviceDescriptor, ExperimentalFeatures, Features, Limits, PowerPreference,
+    SurfaceConfiguration, TextureFormat, TextureUsages, Trace,
+};
+use winit::window::Window;
+
+use super::{
+    AdapterDetails, DepthConfig, RenderError, Renderer, RendererBackends,
+    adapter::{self, SelectedAdapter},
+    target::{RenderTarget, choose_present_mode},
+};
+
+// === RendererBuilder struct
+// Required features and limits fail the build when the adapter lacks
+// them, optional features are enabled only where available. Check
+// Renderer::granted_features() to see what the device ended up with.
+#[derive(Debug, Clone)]
+pub struct RendererBuilder {
+    backend: RendererBackends,
+    adapter: Option<AdapterDetails>,
+    power_preference: PowerPreference,
+    required_features: Features,
+    optional_features: Features,
+    limits: Limits,
+    adapter_limits: bool,
+    sample_count: u32,
+    depth_config: DepthConfig,
+    surface_format: Option<TextureFormat>,
+    alpha_mode: Option<CompositeAlphaMode>,
+    vsync: bool,
+}
+
+impl Default for RendererBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RendererBuilder {
+    pub fn new() -> Self {
+        Self {
+            backend: RendererBackends::Auto,
+            adapter: None,
+            power_preference: PowerPreference::default(),
+            required_features: Features::empty(),
+            optional_features: Features::ADDRESS_MODE_CLAMP_TO_BORDER
+                | Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES,
+            limits: Limits::defaults(),
+            adapter_limits: false,
+            sample_count: 1,
+            depth_config: DepthConfig::default(),
+            surface_format: None,
+            alpha_mode: None,
+            vsync: true,
+        }
+    }
+
+    pub fn backend(mut self, backend: RendererBackends) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    // Uses an adapter returned by Renderer::enumerate_adapters instead
+    // of picking one from the backend and power preference
+    pub fn adapter(mut self, adapter: AdapterDetails) -> Self {
+        self.adapter = Some(adapter);
+        self
+    }
+
+    pub fn power_preference(mut self, power_preference: PowerPreference) -> Self {
+        self.power_preference = power_preference;
+        self
+    }
+
+    pub fn required_features(mut self, features: Features) -> Self {
+        self.required_features = features;
+        self
+    }
+
+    pub fn optional_features(mut self, features: Features) -> Self {
+        self.optional_features = features;
+        self
+    }
+
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    // Requests the best limits the adapter supports instead of the
+    // ones given to limits()
+    pub fn adapter_limits(mut self, adapter_limits: bool) -> Self {
+        self.adapter_limits = adapter_limits;
+        self
+    }
+
+    // MSAA sample count, 1 turns multisampling off. Building fails when
+    // the adapter does not support the count for the surface format,
+    // see Renderer::supported_sample_counts.
+    pub fn sample_count(mut self, sample_count: u32) -> Self {
+        self.sample_count = sample_count;
+        self
+    }
+
+    // Depth testing the pipelines are first created with, saves
+    // rebuilding them with Renderer::set_depth_config afterwards
+    pub fn depth_config(mut self, depth_config: DepthConfig) -> Self {
+        self.depth_config = depth_config;
+        self
+    }
+
+    // Preferred surface format, the first sRGB format of the surface
+    // is used when it is not supported
+    pub fn surface_format(mut self, format: TextureFormat) -> Self {
+        self.surface_format = Some(format);
+        self
+    }
+
+    // Preferred alpha mode, the first one the surface supports
+    // is used when it is not supported
+    pub fn alpha_mode(mut self, alpha_mode: CompositeAlphaMode) -> Self {
+        self.alpha_mode = Some(alpha_mode);
+        self
+    }
+
+    pub fn vsync(mut self, vsync: bool) -> Self {
+        self.vsync = vsync;
+        self
+    }
+
+    pub async fn build(self, window: &Window) -> Result<Renderer> {
+        let size = window.inner_size();
+        let SelectedAdapter {
+            instance,
+            surface,
+            adapter,
+        } = self.select_adapter(Some(window)).await?;
+        let surface = surface.context("Adapter was selected without a surface")?;
+
+        let (device, queue) = self.request_device(&adapter).await?;
+
+        // empty capabilities mean the adapter can not present to the surface
+        let surface_caps = surface.get_capabilities(&adapter);
+        let surface_format = self
+            .surface_format
+            .filter(|format| surface_caps.formats.contains(format))
+            .or_else(|| surface_caps.formats.iter().find(|f| f.is_srgb()).copied())
+            .or_else(|| surface_caps.formats.first().copied())
+            .ok_or_else(|| {
+                RenderError::SurfaceError("Surface supports no texture formats".into())
+            })?;
+        let alpha_mode = self
+            .alpha_mode
+            .filter(|mode| surface_caps.alpha_modes.contains(mode))
+            .or_else(|| surface_caps.alpha_modes.first().copied())
+            .ok_or_else(|| RenderError::SurfaceError("Surface supports no alpha modes".into()))?;
+
+        let config = SurfaceConfiguration {
+            usage: TextureUsages::RENDER_ATTACHMENT,
+            format: surface_format,
+            width: size.width,
+            height: size.height,
+            present_mode: choose_present_mode(
+                Renderer::vsync_present_mode(self.vsync),
+                &surface_caps.present_modes,
+            ),
+            desired_maximum_frame_latency: 2,
+            alpha_mode,
+            view_formats: vec![],
+        };
+        surface.configure(&device, &config);
+
+        Renderer::from_target(
+            instance,
+            adapter,
+            device,
+            queue,
+            RenderTarget::Surface {
+                surface,
+                config,
+                present_modes: surface_caps.present_modes,
+            },
+            (size.width, size.height),
+            self.sample_count,
+            self.depth_config,
+        )
+    }
+
+    // Builds a renderer drawing into an offscreen texture, see
+    // Renderer::new_headless
+    pub async fn build_headless(self, width: u32, height: u32) -> Result<Renderer> {
+        if width == 0 || height == 0 {
+            bail!("Headless renderer needs a non-zero size, got {width}x{height}");
+        }
+        let SelectedAdapter {
+            instance, adapter, ..
+        } = self.select_adapter(None).await?;
+        let (device, queue) = self.request_device(&adapter).await?;
+        let target = RenderTarget::create_offscreen(&device, width, height);
+
+        Renderer::from_target(
+            instance,
+            adapter,
+            device,
+            queue,
+            target,
+            (width, height),
+            self.sample_count,
+            self.depth_config,
+        )
+    }
+
+    async fn select_adapter(&self, window: Option<&Window>) -> Result<SelectedAdapter> {
+        match &self.adapter {
+            Some(details) => adapter::open_adapter(details, window),
+            None => {
+                adapter::select_adapter(self.backend.clone(), self.power_preference, window).await
+            }
+        }
+    }
+
+    async fn request_device(&self, adapter: &wgpu::Adapter) -> Result<(wgpu::Device, wgpu::Queue)> {
+        let supported = adapter.features();
+        let missing = self.required_features - supported;
+        if !missing.is_empty() {
+            bail!("Adapter does not support the required features {missing:?}");
+        }
+
+        let limits = if self.adapter_limits {
+            adapter.limits()
+        } else {
+            self.limits.clone()
+        };
+        let mut unsupported = Vec::new();
+        limits.check_limits_with_fail_fn(&adapter.limits(), false, |name, requested, allowed| {
+            unsupported.push(format!("{name} (requested {requested}, allowed {allowed})"))
+        });
+        if !unsupported.is_empty() {
+            bail!(
+                "Adapter does not support the requested limits: {}",
+                unsupported.join(", ")
+            );
+        }
+
+        adapter
+            .request_device(&DeviceDescriptor {
+                label: None,
+                required_features: self.required_features | (self.optional_features & supported),
+                experimental_features: ExperimentalFeatures::disabled(),
+                required_limits: limits,
+                memory_hints: Default::default(),
+                trace: Trace::Off,
+            })
+            .await
+            .context("Failed to create device")
+    }
+}
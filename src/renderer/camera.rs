@@ -6,14 +6,25 @@
 use bytemuck::{Pod, Zeroable};
 use cgmath::*;
 
+// === Projection enum
+// Perspective takes the vertical field of view in degrees, orthographic
+// the height of the visible area in world units. The width of both
+// follows from the camera aspect ratio.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projection {
+    Perspective { fovy: f32 },
+    Orthographic { height: f32 },
+}
+
 // === Camera struct
 
+#[derive(Debug, Clone, Copy)]
 pub struct Camera {
     eye: Point3<f32>,
     target: Point3<f32>,
     up: Vector3<f32>,
     aspect: f32,
-    fovy: f32,
+    projection: Projection,
     znear: f32,
     zfar: f32,
 }
@@ -41,18 +52,78 @@ impl Camera {
             target,
             up,
             aspect,
-            fovy,
+            projection: Projection::Perspective { fovy },
             znear,
             zfar,
         }
     }
 
-    fn build_view_projection_matrix(&self) -> Matrix4<f32> {
+    pub fn orthographic(
+        eye: Point3<f32>,
+        target: Point3<f32>,
+        up: Vector3<f32>,
+        aspect: f32,
+        height: f32,
+        znear: f32,
+        zfar: f32,
+    ) -> Self {
+        Self {
+            eye,
+            target,
+            up,
+            aspect,
+            projection: Projection::Orthographic { height },
+            znear,
+            zfar,
+        }
+    }
+
+    pub fn eye(&self) -> Point3<f32> {
+        self.eye
+    }
+
+    pub fn target(&self) -> Point3<f32> {
+        self.target
+    }
+
+    pub fn aspect(&self) -> f32 {
+        self.aspect
+    }
+
+    pub fn set_aspect(&mut self, aspect: f32) {
+        self.aspect = aspect;
+    }
+
+    pub fn projection(&self) -> Projection {
+        self.projection
+    }
+
+    pub fn set_projection(&mut self, projection: Projection) {
+        self.projection = projection;
+    }
+
+    pub(crate) fn build_view_projection_matrix(&self) -> Matrix4<f32> {
         let view = Matrix4::look_at_rh(self.eye, self.target, self.up);
 
-        let proj = perspective(Deg(self.fovy), self.aspect, self.znear, self.zfar);
+        let proj = match self.projection {
+            Projection::Perspective { fovy } => {
+                perspective(Deg(fovy), self.aspect, self.znear, self.zfar)
+            }
+            Projection::Orthographic { height } => {
+                let half_height = height / 2.0;
+                let half_width = half_height * self.aspect;
+                ortho(
+                    -half_width,
+                    half_width,
+                    -half_height,
+                    half_height,
+                    self.znear,
+                    self.zfar,
+                )
+            }
+        };
 
-        return OPENGL_TO_WGPU_MATRIX * proj * view;
+        OPENGL_TO_WGPU_MATRIX * proj * view
     }
 }
 
@@ -62,16 +133,20 @@ impl Camera {
 #[derive(Debug, Copy, Clone, Pod, Zeroable)]
 pub struct CameraUniform {
     view_proj: [[f32; 4]; 4],
+    // w is unused, it pads the position to a vec4
+    view_position: [f32; 4],
 }
 
 impl CameraUniform {
     pub(crate) fn new() -> Self {
         Self {
             view_proj: Matrix4::identity().into(),
+            view_position: [0.0; 4],
         }
     }
 
     pub(crate) fn update_view_proj(&mut self, camera: &Camera) {
         self.view_proj = camera.build_view_projection_matrix().into();
+        self.view_position = camera.eye.to_homogeneous().into();
     }
 }
@@ -0,0 +1,144 @@
+//! Bine renderer
+//!
+//! Author: BEKs => 18.10.2026
+//!
+//! 2D camera for games drawn in a plane. Positions are in world units,
+//! which equal pixels at zoom 1 unless pixels_per_unit says otherwise.
+use cgmath::*;
+
+use super::Camera;
+
+// === Camera2D struct
+// Looks down the -z axis at the xy plane with y pointing up. The
+// position is the world point shown at the centre of the viewport.
+#[derive(Debug, Clone, Copy)]
+pub struct Camera2D {
+    pub position: Vector2<f32>,
+    pub zoom: f32,
+    pub rotation: Rad<f32>,
+    pub pixels_per_unit: f32,
+    viewport: (f32, f32),
+}
+
+impl Camera2D {
+    // Depth range covered around the z = 0 plane
+    const DEPTH_RANGE: f32 = 2000.0;
+
+    pub fn new(viewport_width: u32, viewport_height: u32) -> Self {
+        Self {
+            position: Vector2::zero(),
+            zoom: 1.0,
+            rotation: Rad(0.0),
+            pixels_per_unit: 1.0,
+            viewport: (viewport_width.max(1) as f32, viewport_height.max(1) as f32),
+        }
+    }
+
+    pub fn with_pixels_per_unit(mut self, pixels_per_unit: f32) -> Self {
+        self.pixels_per_unit = pixels_per_unit;
+        self
+    }
+
+    pub fn viewport(&self) -> (f32, f32) {
+        self.viewport
+    }
+
+    pub fn set_viewport(&mut self, width: u32, height: u32) {
+        self.viewport = (width.max(1) as f32, height.max(1) as f32);
+    }
+
+    // Size of the visible area in world units
+    pub fn visible_size(&self) -> Vector2<f32> {
+        let scale = self.pixels_per_unit * self.zoom;
+        Vector2::new(self.viewport.0 / scale, self.viewport.1 / scale)
+    }
+
+    // Orthographic camera that can be handed to Renderer::update_camera
+    pub fn to_camera(&self) -> Camera {
+        let half_depth = Self::DEPTH_RANGE / 2.0;
+        let (sin, cos) = self.rotation.sin_cos();
+        Camera::orthographic(
+            Point3::new(self.position.x, self.position.y, half_depth),
+            Point3::new(self.position.x, self.position.y, 0.0),
+            Vector3::new(-sin, cos, 0.0),
+            self.viewport.0 / self.viewport.1,
+            self.visible_size().y,
+            0.0,
+            Self::DEPTH_RANGE,
+        )
+    }
+
+    // Converts a position in window pixels (origin top left, y down),
+    // e.g. the mouse position, into world coordinates
+    pub fn screen_to_world(&self, screen: Vector2<f32>) -> Vector2<f32> {
+        let scale = self.pixels_per_unit * self.zoom;
+        let offset = Vector2::new(
+            (screen.x - self.viewport.0 / 2.0) / scale,
+            (self.viewport.1 / 2.0 - screen.y) / scale,
+        );
+        self.position + Basis2::from_angle(self.rotation).rotate_vector(offset)
+    }
+
+    pub fn world_to_screen(&self, world: Vector2<f32>) -> Vector2<f32> {
+        let scale = self.pixels_per_unit * self.zoom;
+        let offset = Basis2::from_angle(-self.rotation).rotate_vector(world - self.position);
+        Vector2::new(
+            offset.x * scale + self.viewport.0 / 2.0,
+            self.viewport.1 / 2.0 - offset.y * scale,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: Vector2<f32>, b: Vector2<f32>) {
+        assert!((a - b).magnitude() < 1e-3, "{a:?} != {b:?}");
+    }
+
+    fn moved_camera() -> Camera2D {
+        let mut camera = Camera2D::new(800, 600).with_pixels_per_unit(32.0);
+        camera.position = Vector2::new(12.5, -4.0);
+        camera.zoom = 1.5;
+        camera.rotation = Rad(0.6);
+        camera
+    }
+
+    #[test]
+    fn screen_world_round_trip() {
+        let camera = moved_camera();
+        for screen in [
+            Vector2::new(0.0, 0.0),
+            Vector2::new(400.0, 300.0),
+            Vector2::new(799.0, 13.0),
+            Vector2::new(-50.0, 650.0),
+        ] {
+            let world = camera.screen_to_world(screen);
+            assert_close(camera.world_to_screen(world), screen);
+        }
+    }
+
+    #[test]
+    fn viewport_centre_is_camera_position() {
+        let camera = moved_camera();
+        assert_close(
+            camera.screen_to_world(Vector2::new(400.0, 300.0)),
+            camera.position,
+        );
+    }
+
+    #[test]
+    fn screen_position_matches_projection() {
+        let camera = moved_camera();
+        let view_proj = camera.to_camera().build_view_projection_matrix();
+        let world = camera.screen_to_world(Vector2::new(100.0, 50.0));
+        let clip = view_proj * Vector4::new(world.x, world.y, 0.0, 1.0);
+        // ndc has y up, screen pixels have y down
+        let screen = Vector2::new(
+            (clip.x / clip.w + 1.0) / 2.0 * 800.0,
+            (1.0 - clip.y / clip.w) / 2.0 * 600.0,
+        );
+        assert_close(screen, Vector2::new(100.0, 50.0));
+    }
+}
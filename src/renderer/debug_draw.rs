@@ -0,0 +1,158 @@
+//! Bine renderer
+//!
+//! Author: BEKs => 18.10.2026
+//!
+//! Immediate mode debug drawing. Primitives are collected as colored
+//! line segments during a frame through Renderer::debug_draw(), drawn
+//! on top of everything else by render() and cleared afterwards.
+
+use bytemuck::{Pod, Zeroable};
+use cgmath::{EuclideanSpace, InnerSpace, Matrix4, Point3, SquareMatrix, Transform, Vector3};
+
+use super::Camera;
+
+// === DebugVertex struct
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub(crate) struct DebugVertex {
+    position: [f32; 3],
+    color: [f32; 4],
+}
+
+impl DebugVertex {
+    const ATTRIB: [wgpu::VertexAttribute; 2] =
+        wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x4];
+
+    pub(crate) fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<DebugVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIB,
+        }
+    }
+}
+
+// === DebugDraw struct
+// Positions are in world space, colors are rgba in 0..1
+#[derive(Debug, Default)]
+pub struct DebugDraw {
+    // two vertices for every line
+    pub(crate) vertices: Vec<DebugVertex>,
+}
+
+impl DebugDraw {
+    // Segments used for the circles of spheres
+    const CIRCLE_SEGMENTS: u32 = 32;
+
+    pub fn line(&mut self, from: Point3<f32>, to: Point3<f32>, color: [f32; 4]) {
+        self.vertices.push(DebugVertex {
+            position: from.into(),
+            color,
+        });
+        self.vertices.push(DebugVertex {
+            position: to.into(),
+            color,
+        });
+    }
+
+    // Axis aligned box between its min and max corners
+    pub fn aabb(&mut self, min: Point3<f32>, max: Point3<f32>, color: [f32; 4]) {
+        let corner = |i: usize| {
+            Point3::new(
+                if i & 1 == 0 { min.x } else { max.x },
+                if i & 2 == 0 { min.y } else { max.y },
+                if i & 4 == 0 { min.z } else { max.z },
+            )
+        };
+        self.box_edges(&std::array::from_fn(corner), color);
+    }
+
+    // Three circles around the centre, one in each axis plane
+    pub fn sphere(&mut self, center: Point3<f32>, radius: f32, color: [f32; 4]) {
+        let (x, y, z) = (Vector3::unit_x(), Vector3::unit_y(), Vector3::unit_z());
+        self.circle(center, x * radius, y * radius, color);
+        self.circle(center, y * radius, z * radius, color);
+        self.circle(center, z * radius, x * radius, color);
+    }
+
+    // Circle spanned by two perpendicular radius vectors
+    fn circle(&mut self, center: Point3<f32>, u: Vector3<f32>, v: Vector3<f32>, color: [f32; 4]) {
+        let point = |i: u32| {
+            let angle = i as f32 / Self::CIRCLE_SEGMENTS as f32 * std::f32::consts::TAU;
+            center + u * angle.cos() + v * angle.sin()
+        };
+        for i in 0..Self::CIRCLE_SEGMENTS {
+            self.line(point(i), point(i + 1), color);
+        }
+    }
+
+    // The x, y and z axes of a transform in red, green and blue
+    pub fn axes(&mut self, transform: Matrix4<f32>, size: f32) {
+        let origin = transform.transform_point(Point3::origin());
+        let axes = [
+            (Vector3::unit_x(), [1.0, 0.0, 0.0, 1.0]),
+            (Vector3::unit_y(), [0.0, 1.0, 0.0, 1.0]),
+            (Vector3::unit_z(), [0.0, 0.0, 1.0, 1.0]),
+        ];
+        for (axis, color) in axes {
+            let direction = transform.transform_vector(axis).normalize_to(size);
+            self.line(origin, origin + direction, color);
+        }
+    }
+
+    // Square grid in the xz plane, divided into divisions cells per side
+    pub fn grid(&mut self, center: Point3<f32>, size: f32, divisions: u32, color: [f32; 4]) {
+        let divisions = divisions.max(1);
+        let half = size / 2.0;
+        for i in 0..=divisions {
+            let offset = i as f32 / divisions as f32 * size - half;
+            self.line(
+                center + Vector3::new(offset, 0.0, -half),
+                center + Vector3::new(offset, 0.0, half),
+                color,
+            );
+            self.line(
+                center + Vector3::new(-half, 0.0, offset),
+                center + Vector3::new(half, 0.0, offset),
+                color,
+            );
+        }
+    }
+
+    // The volume the camera sees, from its near to its far plane
+    pub fn frustum(&mut self, camera: &Camera, color: [f32; 4]) {
+        let Some(inverse) = camera.build_view_projection_matrix().invert() else {
+            return;
+        };
+        // wgpu clip space has depth in 0..1
+        let corner = |i: usize| {
+            let ndc = Point3::new(
+                if i & 1 == 0 { -1.0 } else { 1.0 },
+                if i & 2 == 0 { -1.0 } else { 1.0 },
+                if i & 4 == 0 { 0.0 } else { 1.0 },
+            );
+            inverse.transform_point(ndc)
+        };
+        self.box_edges(&std::array::from_fn(corner), color);
+    }
+
+    // Twelve edges of a box, corner i has bit 0 set for +x,
+    // bit 1 for +y and bit 2 for +z
+    fn box_edges(&mut self, corners: &[Point3<f32>; 8], color: [f32; 4]) {
+        for i in 0..8 {
+            for bit in [1, 2, 4] {
+                if i & bit == 0 {
+                    self.line(corners[i], corners[i | bit], color);
+                }
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+    }
+}
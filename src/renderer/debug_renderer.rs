@@ -0,0 +1,152 @@
+//! Bine renderer
+//!
+//! Author: BEKs => 18.10.2026
+//!
+//! GPU side of debug drawing. The lines collected in a DebugDraw are
+//! uploaded into one dynamic vertex buffer and drawn with a line list
+//! pipeline in a single draw call.
+
+use super::{
+    debug_draw::{DebugDraw, DebugVertex},
+    material::{PipelineSettings, SharedLayouts},
+};
+
+// === DebugRenderer struct
+pub(crate) struct DebugRenderer {
+    shader: wgpu::ShaderModule,
+    pipeline_layout: wgpu::PipelineLayout,
+    pipeline: wgpu::RenderPipeline,
+
+    vertex_buffer: wgpu::Buffer,
+    // capacity and number of uploaded vertices
+    capacity: usize,
+    vertex_count: u32,
+
+    // lines queued for the current frame
+    pub(crate) lines: DebugDraw,
+}
+
+impl DebugRenderer {
+    const INITIAL_CAPACITY: usize = 1024;
+    const BIND_GROUP: u32 = 0;
+
+    pub(crate) fn new(
+        device: &wgpu::Device,
+        layouts: &SharedLayouts,
+        settings: &PipelineSettings,
+    ) -> Self {
+        let shader = device.create_shader_module(wgpu::include_wgsl!("../../shaders/debug.wgsl"));
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Debug Pipeline Layout"),
+            bind_group_layouts: &[layouts.camera],
+            push_constant_ranges: &[],
+        });
+        let pipeline = Self::create_pipeline(device, &pipeline_layout, &shader, settings);
+
+        Self {
+            shader,
+            pipeline_layout,
+            pipeline,
+            vertex_buffer: Self::create_vertex_buffer(device, Self::INITIAL_CAPACITY),
+            capacity: Self::INITIAL_CAPACITY,
+            vertex_count: 0,
+            lines: DebugDraw::default(),
+        }
+    }
+
+    fn create_pipeline(
+        device: &wgpu::Device,
+        pipeline_layout: &wgpu::PipelineLayout,
+        shader: &wgpu::ShaderModule,
+        settings: &PipelineSettings,
+    ) -> wgpu::RenderPipeline {
+        // debug lines are always visible, on top of the scene
+        let depth_stencil =
+            settings
+                .depth
+                .depth_stencil_state(false)
+                .map(|state| wgpu::DepthStencilState {
+                    depth_compare: wgpu::CompareFunction::Always,
+                    ..state
+                });
+
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Debug Pipeline"),
+            layout: Some(pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: Some("vs_main"),
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                buffers: &[DebugVertex::desc()],
+            },
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::LineList,
+                cull_mode: None,
+                ..Default::default()
+            },
+            depth_stencil,
+            multisample: wgpu::MultisampleState {
+                count: settings.sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point: Some("fs_main"),
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: settings.format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            multiview: None,
+            cache: None,
+        })
+    }
+
+    // Rebuilds the pipeline after the render target state changed
+    pub(crate) fn rebuild_pipeline(&mut self, device: &wgpu::Device, settings: &PipelineSettings) {
+        self.pipeline =
+            Self::create_pipeline(device, &self.pipeline_layout, &self.shader, settings);
+    }
+
+    fn create_vertex_buffer(device: &wgpu::Device, capacity: usize) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Debug Vertex Buffer"),
+            size: (capacity * std::mem::size_of::<DebugVertex>()) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    // Uploads the queued lines
+    pub(crate) fn prepare(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        let vertices = &self.lines.vertices;
+        self.vertex_count = vertices.len() as u32;
+        if vertices.is_empty() {
+            return;
+        }
+        if vertices.len() > self.capacity {
+            self.capacity = vertices.len().next_power_of_two();
+            self.vertex_buffer = Self::create_vertex_buffer(device, self.capacity);
+        }
+        queue.write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(vertices));
+    }
+
+    // Binds the camera itself, to group 0 of the debug pipeline
+    pub(crate) fn draw(&self, render_pass: &mut wgpu::RenderPass, camera: &wgpu::BindGroup) {
+        if self.vertex_count == 0 {
+            return;
+        }
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(Self::BIND_GROUP, camera, &[]);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        render_pass.draw(0..self.vertex_count, 0..1);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.lines.clear();
+        self.vertex_count = 0;
+    }
+}
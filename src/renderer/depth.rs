@@ -0,0 +1,51 @@
+//! Bine renderer
+//!
+//! Author: BEKs => 18.10.2026
+//!
+//! Depth buffer configuration used by the render pipelines
+
+use wgpu::{CompareFunction, DepthBiasState, DepthStencilState, StencilState};
+
+use super::Texture;
+
+// === DepthConfig struct
+// Controls whether depth testing is done and how fragments are compared
+// against the depth buffer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DepthConfig {
+    pub enabled: bool,
+    pub compare: CompareFunction,
+    pub clear_value: f32,
+}
+
+impl Default for DepthConfig {
+    // Closer fragments win, and the buffer is cleared to the far plane
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            compare: CompareFunction::Less,
+            clear_value: 1.0,
+        }
+    }
+}
+
+impl DepthConfig {
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            ..Default::default()
+        }
+    }
+
+    // Transparent materials still test against the depth buffer but
+    // do not write to it, so they never hide what is drawn behind them
+    pub(crate) fn depth_stencil_state(&self, depth_write: bool) -> Option<DepthStencilState> {
+        self.enabled.then(|| DepthStencilState {
+            format: Texture::DEPTH_FORMAT,
+            depth_write_enabled: depth_write,
+            depth_compare: self.compare,
+            stencil: StencilState::default(),
+            bias: DepthBiasState::default(),
+        })
+    }
+}
@@ -0,0 +1,170 @@
+//! Bine renderer
+//!
+//! Author: BEKs => 18.10.2026
+//!
+//! Draw list collected from the game during on_draw
+
+use std::{cmp::Ordering, ops::Range};
+
+use cgmath::{InnerSpace, Point3};
+
+use super::{MaterialHandle, MeshHandle, TextureHandle, instance::InstanceRaw};
+
+// === DrawItem struct
+// A mesh drawn with a range of instances from the frame's
+// instance data, issued as a single instanced draw call.
+pub(crate) struct DrawItem {
+    pub(crate) material: MaterialHandle,
+    pub(crate) mesh: MeshHandle,
+    pub(crate) texture: TextureHandle,
+    pub(crate) instances: Range<u32>,
+    pub(crate) transparent: bool,
+}
+
+// === DrawList struct
+// Everything submitted for the current frame. The list is
+// cleared once the frame has been rendered.
+pub(crate) struct DrawList {
+    items: Vec<DrawItem>,
+    instances: Vec<InstanceRaw>,
+}
+
+impl DrawList {
+    pub(crate) fn new() -> Self {
+        Self {
+            items: Vec::new(),
+            instances: Vec::new(),
+        }
+    }
+
+    // Consecutive draws of the same material, mesh and texture are
+    // merged into one draw call. Transparent instances get an item each
+    // so they can be sorted individually.
+    pub(crate) fn push(
+        &mut self,
+        material: MaterialHandle,
+        mesh: MeshHandle,
+        texture: TextureHandle,
+        instances: impl IntoIterator<Item = InstanceRaw>,
+        transparent: bool,
+    ) {
+        let start = self.instances.len() as u32;
+        self.instances.extend(instances);
+        let end = self.instances.len() as u32;
+        if start == end {
+            return;
+        }
+
+        if transparent {
+            self.items.extend((start..end).map(|instance| DrawItem {
+                material,
+                mesh,
+                texture,
+                instances: instance..instance + 1,
+                transparent,
+            }));
+            return;
+        }
+
+        match self.items.last_mut() {
+            Some(last)
+                if !last.transparent
+                    && last.material == material
+                    && last.mesh == mesh
+                    && last.texture == texture =>
+            {
+                last.instances.end = end;
+            }
+            _ => self.items.push(DrawItem {
+                material,
+                mesh,
+                texture,
+                instances: start..end,
+                transparent,
+            }),
+        }
+    }
+
+    // Moves the transparent items behind the opaque ones, sorted back to
+    // front along the view direction. Opaque items keep their order.
+    // Without a view direction (eye on the target) they are sorted by
+    // their distance to the eye instead.
+    pub(crate) fn sort_transparent(&mut self, eye: Point3<f32>, target: Point3<f32>) {
+        let view = target - eye;
+        let forward = (view.magnitude2() > f32::EPSILON).then(|| view.normalize());
+        let instances = &self.instances;
+        let depth = |item: &DrawItem| {
+            let position = instances[item.instances.start as usize].position();
+            let offset = Point3::from(position) - eye;
+            match forward {
+                Some(forward) => offset.dot(forward),
+                None => offset.magnitude(),
+            }
+        };
+        self.items
+            .sort_by(|a, b| match (a.transparent, b.transparent) {
+                (false, false) => Ordering::Equal,
+                (false, true) => Ordering::Less,
+                (true, false) => Ordering::Greater,
+                (true, true) => depth(b).total_cmp(&depth(a)),
+            });
+    }
+
+    pub(crate) fn items(&self) -> &[DrawItem] {
+        &self.items
+    }
+
+    pub(crate) fn instances(&self) -> &[InstanceRaw] {
+        &self.instances
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.items.clear();
+        self.instances.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{Matrix4, Vector3};
+
+    use super::*;
+    use crate::renderer::Instance;
+
+    fn transparent_list(positions: &[[f32; 3]]) -> DrawList {
+        let mut list = DrawList::new();
+        for position in positions {
+            let instance = Instance::new(Matrix4::from_translation(Vector3::from(*position)));
+            list.push(
+                MaterialHandle(0),
+                MeshHandle(0),
+                TextureHandle(0),
+                [instance.to_raw()],
+                true,
+            );
+        }
+        list
+    }
+
+    fn sorted_z(list: &DrawList) -> Vec<f32> {
+        list.items()
+            .iter()
+            .map(|item| list.instances()[item.instances.start as usize].position()[2])
+            .collect()
+    }
+
+    #[test]
+    fn transparent_items_sort_back_to_front() {
+        let mut list = transparent_list(&[[0.0, 0.0, -2.0], [0.0, 0.0, -8.0], [0.0, 0.0, -5.0]]);
+        list.sort_transparent(Point3::new(0.0, 0.0, 0.0), Point3::new(0.0, 0.0, -1.0));
+        assert_eq!(sorted_z(&list), [-8.0, -5.0, -2.0]);
+    }
+
+    #[test]
+    fn eye_on_target_sorts_by_distance() {
+        let mut list = transparent_list(&[[0.0, 0.0, 1.0], [0.0, 0.0, -6.0], [0.0, 0.0, 3.0]]);
+        let eye = Point3::new(0.0, 0.0, 0.0);
+        list.sort_transparent(eye, eye);
+        assert_eq!(sorted_z(&list), [-6.0, 3.0, 1.0]);
+    }
+}
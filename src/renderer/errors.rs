@@ -0,0 +1,56 @@
+//! Bine renderer errors.
+//!
+//! Author: BEKs => 18.10.2026
+//!
+//! Errors returned while creating and rendering with the Renderer.
+//! Lost and outdated surfaces are recovered from inside the renderer
+//! and never reach the caller.
+
+use std::error::Error;
+use std::fmt;
+
+// === RenderError enum
+#[derive(Debug)]
+pub enum RenderError {
+    // the GPU ran out of memory, the renderer can not continue
+    OutOfMemory,
+    // the device was lost (driver reset, GPU removed), the renderer
+    // has to be created again
+    DeviceLost(String),
+    // any other surface error reported by the backend
+    SurfaceError(String),
+}
+
+// Implement Display
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenderError::OutOfMemory => write!(f, "Out of GPU memory"),
+            RenderError::DeviceLost(msg) => write!(f, "Device lost: {}", msg),
+            RenderError::SurfaceError(msg) => write!(f, "Surface error: {}", msg),
+        }
+    }
+}
+
+// Implement std::error::Error
+impl Error for RenderError {}
+
+// === AdapterNotFound struct
+// Returned when no backend produced a usable adapter. Every attempt,
+// including the software fallback adapter, is listed with its reason.
+#[derive(Debug)]
+pub struct AdapterNotFound {
+    pub tried: Vec<String>,
+}
+
+impl fmt::Display for AdapterNotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "No suitable GPU adapter found, tried:")?;
+        for attempt in &self.tried {
+            write!(f, "\n  {}", attempt)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for AdapterNotFound {}
@@ -0,0 +1,152 @@
+//! Bine renderer
+//!
+//! Author: BEKs => 18.10.2026
+//!
+//! Development helper that watches shader files on disk so
+//! materials can be recompiled without rebuilding the game
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+// === ShaderWatcher struct
+// Polls the modification time of every watched file. Polling is
+// throttled so calling it once per frame stays cheap.
+pub(crate) struct ShaderWatcher {
+    shader_dir: PathBuf,
+    modified: HashMap<PathBuf, Option<SystemTime>>,
+    last_poll: Instant,
+}
+
+impl ShaderWatcher {
+    const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+    pub(crate) fn new(shader_dir: &Path) -> Self {
+        Self {
+            shader_dir: shader_dir.to_path_buf(),
+            modified: HashMap::new(),
+            last_poll: Instant::now(),
+        }
+    }
+
+    pub(crate) fn shader_dir(&self) -> &Path {
+        &self.shader_dir
+    }
+
+    pub(crate) fn watch(&mut self, path: &Path) {
+        self.modified
+            .entry(path.to_path_buf())
+            .or_insert_with(|| Self::modified_time(path));
+    }
+
+    // Files that were written since the last poll
+    pub(crate) fn changed_files(&mut self) -> Vec<PathBuf> {
+        if self.last_poll.elapsed() < Self::POLL_INTERVAL {
+            return Vec::new();
+        }
+        self.last_poll = Instant::now();
+
+        let mut changed = Vec::new();
+        for (path, last_modified) in self.modified.iter_mut() {
+            let modified = Self::modified_time(path);
+            if modified != *last_modified {
+                *last_modified = modified;
+                changed.push(path.clone());
+            }
+        }
+        changed
+    }
+
+    fn modified_time(path: &Path) -> Option<SystemTime> {
+        fs::metadata(path).and_then(|m| m.modified()).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    // A fresh directory per test under the system temp dir
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("bine-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_shader(path: &Path, modified: SystemTime) {
+        fs::write(path, "// shader").unwrap();
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+    }
+
+    // Lets the next changed_files() call poll again
+    fn expire(watcher: &mut ShaderWatcher) {
+        watcher.last_poll = Instant::now() - ShaderWatcher::POLL_INTERVAL;
+    }
+
+    #[test]
+    fn new_modification_time_is_reported_once() {
+        let dir = temp_dir("modified");
+        let path = dir.join("basic.wgsl");
+        let start = SystemTime::now() - Duration::from_secs(60);
+        write_shader(&path, start);
+
+        let mut watcher = ShaderWatcher::new(&dir);
+        watcher.watch(&path);
+        expire(&mut watcher);
+        assert!(watcher.changed_files().is_empty());
+
+        write_shader(&path, start + Duration::from_secs(1));
+        expire(&mut watcher);
+        assert_eq!(watcher.changed_files(), [path.clone()]);
+        expire(&mut watcher);
+        assert!(watcher.changed_files().is_empty());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn deleted_file_is_reported() {
+        let dir = temp_dir("deleted");
+        let path = dir.join("lit.wgsl");
+        write_shader(&path, SystemTime::now());
+
+        let mut watcher = ShaderWatcher::new(&dir);
+        watcher.watch(&path);
+        fs::remove_file(&path).unwrap();
+        expire(&mut watcher);
+        assert_eq!(watcher.changed_files(), [path.clone()]);
+        expire(&mut watcher);
+        assert!(watcher.changed_files().is_empty());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn polls_inside_the_interval_report_nothing() {
+        let dir = temp_dir("throttled");
+        let path = dir.join("basic.wgsl");
+        let start = SystemTime::now() - Duration::from_secs(60);
+        write_shader(&path, start);
+
+        let mut watcher = ShaderWatcher::new(&dir);
+        watcher.watch(&path);
+        write_shader(&path, start + Duration::from_secs(1));
+        // the watcher was just created, the interval has not passed
+        assert!(watcher.changed_files().is_empty());
+
+        expire(&mut watcher);
+        assert_eq!(watcher.changed_files().len(), 1);
+        write_shader(&path, start + Duration::from_secs(2));
+        assert!(watcher.changed_files().is_empty());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
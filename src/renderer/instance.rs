@@ -0,0 +1,84 @@
+//! Bine renderer
+//!
+//! Author: BEKs => 18.10.2026
+//!
+//! Per-instance data streamed next to the vertex buffer
+
+use bytemuck::{Pod, Zeroable};
+use cgmath::{Matrix4, SquareMatrix};
+use wgpu::{BufferAddress, VertexBufferLayout};
+
+// === Instance struct
+// Placement and color of one copy of a mesh. The tint is
+// multiplied with the sampled texture color.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Instance {
+    pub transform: Matrix4<f32>,
+    pub tint: [f32; 4],
+}
+
+impl Default for Instance {
+    fn default() -> Self {
+        Self::new(Matrix4::identity())
+    }
+}
+
+impl From<Matrix4<f32>> for Instance {
+    fn from(transform: Matrix4<f32>) -> Self {
+        Self::new(transform)
+    }
+}
+
+impl Instance {
+    pub const WHITE: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+    pub fn new(transform: Matrix4<f32>) -> Self {
+        Self {
+            transform,
+            tint: Self::WHITE,
+        }
+    }
+
+    pub fn with_tint(mut self, tint: [f32; 4]) -> Self {
+        self.tint = tint;
+        self
+    }
+
+    pub(crate) fn to_raw(self) -> InstanceRaw {
+        InstanceRaw {
+            model: self.transform.into(),
+            tint: self.tint,
+        }
+    }
+}
+
+// === InstanceRaw struct
+// Model matrix of a single draw, laid out as four vec4 columns
+// since wgsl has no matrix vertex attributes, followed by the tint.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub(crate) struct InstanceRaw {
+    model: [[f32; 4]; 4],
+    tint: [f32; 4],
+}
+
+impl InstanceRaw {
+    // Locations 0..5 are left to the vertex types
+    const ATTRIB: [wgpu::VertexAttribute; 5] = wgpu::vertex_attr_array![
+        5 => Float32x4, 6 => Float32x4, 7 => Float32x4, 8 => Float32x4, 9 => Float32x4
+    ];
+
+    pub(crate) fn desc<'a>() -> VertexBufferLayout<'a> {
+        VertexBufferLayout {
+            array_stride: std::mem::size_of::<InstanceRaw>() as BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::ATTRIB,
+        }
+    }
+
+    // Translation of the model matrix
+    pub(crate) fn position(&self) -> [f32; 3] {
+        let [x, y, z, _] = self.model[3];
+        [x, y, z]
+    }
+}
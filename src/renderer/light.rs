@@ -0,0 +1,416 @@
+//! Bine renderer
+//!
+//! Author: BEKs => 18.10.2026
+//!
+//! Lights for the lit material. The scene has an ambient term, one
+//! directional light and up to MAX_POINT_LIGHTS point and MAX_SPOT_LIGHTS
+//! spot lights, uploaded every frame and bound next to the camera.
+
+use bytemuck::{Pod, Zeroable};
+use cgmath::{Angle, Deg, InnerSpace, Point3, Rad, Vector3};
+use wgpu::util::DeviceExt;
+
+use super::{MaterialDescriptor, NormalVertex, registry::Registry};
+
+// must match the array sizes in lit.wgsl
+pub const MAX_POINT_LIGHTS: usize = 8;
+pub const MAX_SPOT_LIGHTS: usize = 8;
+
+// === LightHandle struct
+// Returned from Renderer::add_light and used to move
+// or remove the light again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LightHandle(pub(crate) u32);
+
+// === Attenuation struct
+// Light at distance d is scaled by 1 / (constant + linear * d +
+// quadratic * d * d). Independent of the factors it fades out
+// smoothly towards the range of the light.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Attenuation {
+    pub constant: f32,
+    pub linear: f32,
+    pub quadratic: f32,
+}
+
+impl Default for Attenuation {
+    fn default() -> Self {
+        Self {
+            constant: 1.0,
+            linear: 0.0,
+            quadratic: 1.0,
+        }
+    }
+}
+
+impl Attenuation {
+    // Full strength up to the fade out at the range
+    pub const NONE: Self = Self {
+        constant: 1.0,
+        linear: 0.0,
+        quadratic: 0.0,
+    };
+}
+
+// === DirectionalLight struct
+// Light from far away like the sun, the direction points
+// from the light into the scene.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DirectionalLight {
+    pub direction: Vector3<f32>,
+    // linear rgb
+    pub color: [f32; 3],
+    pub intensity: f32,
+}
+
+impl DirectionalLight {
+    pub fn new(direction: Vector3<f32>, color: [f32; 3], intensity: f32) -> Self {
+        Self {
+            direction,
+            color,
+            intensity,
+        }
+    }
+}
+
+// === PointLight struct
+// Shines in every direction and reaches no further than its range
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointLight {
+    pub position: Point3<f32>,
+    pub color: [f32; 3],
+    pub intensity: f32,
+    pub range: f32,
+    pub attenuation: Attenuation,
+}
+
+impl PointLight {
+    pub fn new(position: Point3<f32>, color: [f32; 3], intensity: f32, range: f32) -> Self {
+        Self {
+            position,
+            color,
+            intensity,
+            range,
+            attenuation: Attenuation::default(),
+        }
+    }
+
+    pub fn with_attenuation(mut self, attenuation: Attenuation) -> Self {
+        self.attenuation = attenuation;
+        self
+    }
+}
+
+// === SpotLight struct
+// A point light limited to a cone around its direction. The angles
+// are in radians, measured from the direction. The light is at full
+// strength inside the inner angle and fades out towards the outer
+// one. The outer angle is kept at most pi and the inner one below it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpotLight {
+    pub position: Point3<f32>,
+    pub direction: Vector3<f32>,
+    pub color: [f32; 3],
+    pub intensity: f32,
+    pub range: f32,
+    pub attenuation: Attenuation,
+    pub inner_angle: Rad<f32>,
+    pub outer_angle: Rad<f32>,
+}
+
+impl SpotLight {
+    pub fn new(
+        position: Point3<f32>,
+        direction: Vector3<f32>,
+        color: [f32; 3],
+        intensity: f32,
+        range: f32,
+    ) -> Self {
+        Self {
+            position,
+            direction,
+            color,
+            intensity,
+            range,
+            attenuation: Attenuation::default(),
+            inner_angle: Deg(20.0).into(),
+            outer_angle: Deg(30.0).into(),
+        }
+    }
+
+    pub fn with_attenuation(mut self, attenuation: Attenuation) -> Self {
+        self.attenuation = attenuation;
+        self
+    }
+
+    // Angles given in the wrong order are swapped
+    pub fn with_cone(
+        mut self,
+        inner_angle: impl Into<Rad<f32>>,
+        outer_angle: impl Into<Rad<f32>>,
+    ) -> Self {
+        let (inner, outer) = (inner_angle.into(), outer_angle.into());
+        (self.inner_angle, self.outer_angle) = if inner <= outer {
+            (inner, outer)
+        } else {
+            (outer, inner)
+        };
+        self
+    }
+
+    // Cosines of the inner and outer angle for the shader. Its
+    // smoothstep needs the inner cosine strictly above the outer one,
+    // so equal angles get a narrow fade instead of none.
+    fn cone_cosines(&self) -> (f32, f32) {
+        const MIN_FADE: f32 = 1.0e-3;
+        let outer = self.outer_angle.0.clamp(MIN_FADE, std::f32::consts::PI);
+        let inner = self.inner_angle.0.clamp(0.0, outer - MIN_FADE);
+        (Rad(inner).cos(), Rad(outer).cos())
+    }
+}
+
+// === Light enum
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Light {
+    Point(PointLight),
+    Spot(SpotLight),
+}
+
+impl From<PointLight> for Light {
+    fn from(light: PointLight) -> Self {
+        Self::Point(light)
+    }
+}
+
+impl From<SpotLight> for Light {
+    fn from(light: SpotLight) -> Self {
+        Self::Spot(light)
+    }
+}
+
+impl Light {
+    pub fn position(&self) -> Point3<f32> {
+        match self {
+            Self::Point(light) => light.position,
+            Self::Spot(light) => light.position,
+        }
+    }
+
+    pub fn set_position(&mut self, position: Point3<f32>) {
+        match self {
+            Self::Point(light) => light.position = position,
+            Self::Spot(light) => light.position = position,
+        }
+    }
+}
+
+// === LitMaterial struct
+// Uniforms of the built-in lit shader. The diffuse color comes from
+// the texture and instance tint, the specular highlight gets sharper
+// with higher shininess.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable)]
+pub struct LitMaterial {
+    pub specular_color: [f32; 3],
+    pub shininess: f32,
+}
+
+impl Default for LitMaterial {
+    fn default() -> Self {
+        Self {
+            specular_color: [0.5, 0.5, 0.5],
+            shininess: 32.0,
+        }
+    }
+}
+
+impl LitMaterial {
+    pub(crate) const SHADER: &str = include_str!("../../shaders/lit.wgsl");
+
+    // Descriptor for Renderer::create_material, meshes drawn
+    // with it are uploaded with NormalVertex
+    pub fn descriptor(&self, label: &str) -> MaterialDescriptor {
+        MaterialDescriptor::new(label, Self::SHADER)
+            .with_vertex::<NormalVertex>()
+            .with_uniforms(self)
+    }
+}
+
+// === Raw light structs
+// Laid out like the structs in lit.wgsl, vec3 members are padded to 16 bytes
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, Pod, Zeroable)]
+struct DirectionalLightRaw {
+    direction: [f32; 3],
+    intensity: f32,
+    color: [f32; 3],
+    _padding: f32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, Pod, Zeroable)]
+struct PointLightRaw {
+    position: [f32; 3],
+    intensity: f32,
+    color: [f32; 3],
+    range: f32,
+    attenuation: [f32; 3],
+    _padding: f32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, Pod, Zeroable)]
+struct SpotLightRaw {
+    position: [f32; 3],
+    intensity: f32,
+    color: [f32; 3],
+    range: f32,
+    direction: [f32; 3],
+    cos_inner: f32,
+    attenuation: [f32; 3],
+    cos_outer: f32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct LightsUniform {
+    ambient: [f32; 3],
+    point_count: u32,
+    spot_count: u32,
+    _padding: [u32; 3],
+    directional: DirectionalLightRaw,
+    points: [PointLightRaw; MAX_POINT_LIGHTS],
+    spots: [SpotLightRaw; MAX_SPOT_LIGHTS],
+}
+
+fn attenuation(attenuation: Attenuation) -> [f32; 3] {
+    [
+        attenuation.constant,
+        attenuation.linear,
+        attenuation.quadratic,
+    ]
+}
+
+// === SceneLights struct
+// Lights set by the game and the uniform buffer they are uploaded to
+pub(crate) struct SceneLights {
+    pub(crate) ambient: [f32; 3],
+    pub(crate) directional: Option<DirectionalLight>,
+    pub(crate) lights: Registry<Light>,
+    pub(crate) buffer: wgpu::Buffer,
+}
+
+impl SceneLights {
+    const DEFAULT_AMBIENT: [f32; 3] = [0.1, 0.1, 0.1];
+
+    pub(crate) fn new(device: &wgpu::Device) -> Self {
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Light Buffer"),
+            contents: bytemuck::bytes_of(&LightsUniform::zeroed()),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        Self {
+            ambient: Self::DEFAULT_AMBIENT,
+            directional: None,
+            lights: Registry::new(),
+            buffer,
+        }
+    }
+
+    pub(crate) fn count(&self, spot: bool) -> usize {
+        self.lights
+            .values()
+            .filter(|light| matches!(light, Light::Spot(_)) == spot)
+            .count()
+    }
+
+    // Lights beyond the maximum, only possible by changing
+    // a point light into a spot light or back, are left out
+    pub(crate) fn write(&self, queue: &wgpu::Queue) {
+        let mut uniform = LightsUniform::zeroed();
+        uniform.ambient = self.ambient;
+        if let Some(light) = self.directional {
+            uniform.directional = DirectionalLightRaw {
+                direction: light.direction.normalize().into(),
+                intensity: light.intensity,
+                color: light.color,
+                _padding: 0.0,
+            };
+        }
+
+        for light in self.lights.values() {
+            match light {
+                Light::Point(light) if (uniform.point_count as usize) < MAX_POINT_LIGHTS => {
+                    uniform.points[uniform.point_count as usize] = PointLightRaw {
+                        position: light.position.into(),
+                        intensity: light.intensity,
+                        color: light.color,
+                        range: light.range,
+                        attenuation: attenuation(light.attenuation),
+                        _padding: 0.0,
+                    };
+                    uniform.point_count += 1;
+                }
+                Light::Spot(light) if (uniform.spot_count as usize) < MAX_SPOT_LIGHTS => {
+                    let (cos_inner, cos_outer) = light.cone_cosines();
+                    uniform.spots[uniform.spot_count as usize] = SpotLightRaw {
+                        position: light.position.into(),
+                        intensity: light.intensity,
+                        color: light.color,
+                        range: light.range,
+                        direction: light.direction.normalize().into(),
+                        cos_inner,
+                        attenuation: attenuation(light.attenuation),
+                        cos_outer,
+                    };
+                    uniform.spot_count += 1;
+                }
+                _ => {}
+            }
+        }
+        queue.write_buffer(&self.buffer, 0, bytemuck::bytes_of(&uniform));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spot() -> SpotLight {
+        SpotLight::new(
+            Point3::new(0.0, 0.0, 0.0),
+            -Vector3::unit_z(),
+            [1.0; 3],
+            1.0,
+            10.0,
+        )
+    }
+
+    #[test]
+    fn cone_angles_are_radians() {
+        let light = spot().with_cone(Deg(10.0), Rad(0.5));
+        assert_eq!(light.inner_angle, Deg(10.0).into());
+        assert_eq!(light.outer_angle, Rad(0.5));
+        let (cos_inner, cos_outer) = light.cone_cosines();
+        assert!((cos_outer - 0.5f32.cos()).abs() < 1e-6);
+        assert!(cos_inner > cos_outer);
+    }
+
+    #[test]
+    fn swapped_cone_angles_are_reordered() {
+        let light = spot().with_cone(Rad(0.6), Rad(0.2));
+        assert_eq!((light.inner_angle, light.outer_angle), (Rad(0.2), Rad(0.6)));
+    }
+
+    #[test]
+    fn cone_cosines_are_strictly_ordered() {
+        for (inner, outer) in [(0.3, 0.3), (0.0, 0.0), (0.8, 0.2), (4.0, 5.0), (-1.0, -0.5)] {
+            let mut light = spot();
+            light.inner_angle = Rad(inner);
+            light.outer_angle = Rad(outer);
+            let (cos_inner, cos_outer) = light.cone_cosines();
+            assert!(cos_inner > cos_outer, "{inner} {outer}");
+        }
+    }
+}
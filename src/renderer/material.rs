@@ -0,0 +1,407 @@
+//! Bine renderer
+//!
+//! Author: BEKs => 18.10.2026
+//!
+//! Materials pair a WGSL shader with the resources it declares.
+//! Every material pipeline shares the same bind group layout:
+//!
+//! group 0 => texture + sampler of the draw (binding 0 and 1)
+//! group 1 => camera uniform (binding 0) and lights (binding 1)
+//!            supplied by the engine
+//! group 2 => material uniform buffer (binding 0) followed by the
+//!            material textures as texture/sampler pairs (binding 1, 2, ...)
+//!
+//! and the vertex stage receives the vertex type of the material, Vertex
+//! unless set with MaterialDescriptor::with_vertex, at locations 0..5 and
+//! the per-instance model matrix and tint at locations 5..10.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, bail};
+use bytemuck::Pod;
+use wgpu::util::DeviceExt;
+
+use super::{
+    BlendMode, DepthConfig, Texture, TextureHandle, Vertex, VertexLayout, instance::InstanceRaw,
+};
+
+pub const TEXTURE_BIND_GROUP: u32 = 0;
+pub const CAMERA_BIND_GROUP: u32 = 1;
+pub const MATERIAL_BIND_GROUP: u32 = 2;
+
+// === MaterialHandle struct
+// Returned from Renderer::create_material and used to refer to the
+// material when submitting draws.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MaterialHandle(pub(crate) u32);
+
+// === MaterialDescriptor struct
+// Everything needed to build a material. The uniform bytes only set the
+// initial contents, the size of the buffer is fixed from then on.
+// Materials with a path are recompiled when shader hot-reload is on.
+#[derive(Debug, Clone)]
+pub struct MaterialDescriptor {
+    pub label: String,
+    pub source: String,
+    pub path: Option<PathBuf>,
+    pub vertex_entry: String,
+    pub fragment_entry: String,
+    pub uniforms: Option<Vec<u8>>,
+    pub textures: Vec<TextureHandle>,
+    pub blend: BlendMode,
+    // faces that are not drawn, None draws both sides
+    pub cull_mode: Option<wgpu::Face>,
+    // layout of the vertices of the meshes drawn with the material
+    pub vertex_layout: wgpu::VertexBufferLayout<'static>,
+}
+
+impl MaterialDescriptor {
+    pub fn new(label: &str, source: &str) -> Self {
+        Self {
+            label: label.into(),
+            source: source.into(),
+            path: None,
+            vertex_entry: "vs_main".into(),
+            fragment_entry: "fs_main".into(),
+            uniforms: None,
+            textures: Vec::new(),
+            blend: BlendMode::Opaque,
+            cull_mode: Some(wgpu::Face::Back),
+            vertex_layout: Vertex::desc(),
+        }
+    }
+
+    // Reads the shader source from disk and remembers the path
+    // so the material can be hot-reloaded
+    pub fn from_file(label: &str, path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read shader {}", path.display()))?;
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            ..Self::new(label, &source)
+        })
+    }
+
+    pub fn with_uniforms<T: Pod>(mut self, uniforms: &T) -> Self {
+        self.uniforms = Some(bytemuck::bytes_of(uniforms).to_vec());
+        self
+    }
+
+    pub fn with_texture(mut self, texture: TextureHandle) -> Self {
+        self.textures.push(texture);
+        self
+    }
+
+    pub fn with_blend(mut self, blend: BlendMode) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    // Back faces are culled unless set otherwise, e.g. None for
+    // leaves and other surfaces seen from both sides
+    pub fn with_cull_mode(mut self, cull_mode: Option<wgpu::Face>) -> Self {
+        self.cull_mode = cull_mode;
+        self
+    }
+
+    // Builds the pipeline for meshes uploaded with vertices of type V
+    pub fn with_vertex<V: VertexLayout>(mut self) -> Self {
+        self.vertex_layout = V::desc();
+        self
+    }
+}
+
+// === PipelineSettings struct
+// Render target state every material pipeline has to agree on
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PipelineSettings {
+    pub(crate) format: wgpu::TextureFormat,
+    pub(crate) depth: DepthConfig,
+    pub(crate) sample_count: u32,
+}
+
+// === SharedLayouts struct
+// Bind group layouts owned by the renderer for the groups
+// it fills in itself.
+pub(crate) struct SharedLayouts<'a> {
+    pub(crate) texture: &'a wgpu::BindGroupLayout,
+    pub(crate) camera: &'a wgpu::BindGroupLayout,
+}
+
+// === Material struct
+pub(crate) struct Material {
+    desc: MaterialDescriptor,
+    shader: wgpu::ShaderModule,
+    pipeline_layout: wgpu::PipelineLayout,
+    pub(crate) pipeline: wgpu::RenderPipeline,
+    pub(crate) bind_group: Option<wgpu::BindGroup>,
+    uniform_buffer: Option<wgpu::Buffer>,
+}
+
+impl Material {
+    // Compiles the shader and builds the pipeline. Shader and pipeline
+    // errors are caught and returned instead of aborting the program.
+    pub(crate) fn new(
+        device: &wgpu::Device,
+        desc: MaterialDescriptor,
+        layouts: &SharedLayouts,
+        textures: &[&Texture],
+        settings: &PipelineSettings,
+    ) -> Result<Self> {
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+
+        // wgsl rounds uniform structs up to 16 bytes, so the buffer is padded to match
+        let uniform_buffer = desc.uniforms.as_ref().map(|uniforms| {
+            let mut contents = uniforms.clone();
+            contents.resize(uniforms.len().next_multiple_of(16).max(16), 0);
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(&desc.label),
+                contents: &contents,
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            })
+        });
+
+        let material_layout = Self::create_bind_group_layout(device, &desc);
+        let bind_group = material_layout.as_ref().map(|layout| {
+            let mut entries = Vec::new();
+            if let Some(buffer) = &uniform_buffer {
+                entries.push(wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: buffer.as_entire_binding(),
+                });
+            }
+            for (binding, texture) in (1u32..).step_by(2).zip(textures) {
+                entries.push(wgpu::BindGroupEntry {
+                    binding,
+                    resource: wgpu::BindingResource::TextureView(&texture.view),
+                });
+                entries.push(wgpu::BindGroupEntry {
+                    binding: binding + 1,
+                    resource: wgpu::BindingResource::Sampler(&texture.sampler),
+                });
+            }
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some(&desc.label),
+                layout,
+                entries: &entries,
+            })
+        });
+
+        let mut bind_group_layouts = vec![layouts.texture, layouts.camera];
+        if let Some(layout) = &material_layout {
+            bind_group_layouts.push(layout);
+        }
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some(&desc.label),
+            bind_group_layouts: &bind_group_layouts,
+            push_constant_ranges: &[],
+        });
+
+        let shader = Self::create_shader(device, &desc.label, &desc.source);
+        let pipeline = Self::create_pipeline(device, &desc, &pipeline_layout, &shader, settings);
+
+        if let Some(message) = Self::pop_validation_error(device) {
+            bail!("Failed to build material {}: {message}", desc.label);
+        }
+
+        Ok(Self {
+            desc,
+            shader,
+            pipeline_layout,
+            pipeline,
+            bind_group,
+            uniform_buffer,
+        })
+    }
+
+    fn create_shader(device: &wgpu::Device, label: &str, source: &str) -> wgpu::ShaderModule {
+        device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some(label),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        })
+    }
+
+    // Closes the error scope opened before building, the description
+    // of a validation error carries the shader diagnostics.
+    fn pop_validation_error(device: &wgpu::Device) -> Option<String> {
+        pollster::block_on(device.pop_error_scope()).map(|error| match error {
+            wgpu::Error::Validation { description, .. } => description,
+            other => other.to_string(),
+        })
+    }
+
+    fn create_bind_group_layout(
+        device: &wgpu::Device,
+        desc: &MaterialDescriptor,
+    ) -> Option<wgpu::BindGroupLayout> {
+        let mut entries = Vec::new();
+        if desc.uniforms.is_some() {
+            entries.push(wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            });
+        }
+        for binding in (1u32..).step_by(2).take(desc.textures.len()) {
+            entries.push(wgpu::BindGroupLayoutEntry {
+                binding,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            });
+            entries.push(wgpu::BindGroupLayoutEntry {
+                binding: binding + 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            });
+        }
+
+        (!entries.is_empty()).then(|| {
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some(&desc.label),
+                entries: &entries,
+            })
+        })
+    }
+
+    fn create_pipeline(
+        device: &wgpu::Device,
+        desc: &MaterialDescriptor,
+        pipeline_layout: &wgpu::PipelineLayout,
+        shader: &wgpu::ShaderModule,
+        settings: &PipelineSettings,
+    ) -> wgpu::RenderPipeline {
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(&desc.label),
+            layout: Some(pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: Some(&desc.vertex_entry),
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                buffers: &[desc.vertex_layout.clone(), InstanceRaw::desc()],
+            },
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: desc.cull_mode,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: settings
+                .depth
+                .depth_stencil_state(!desc.blend.is_transparent()),
+            multisample: wgpu::MultisampleState {
+                count: settings.sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point: Some(&desc.fragment_entry),
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: settings.format,
+                    blend: Some(desc.blend.blend_state()),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            multiview: None,
+            cache: None,
+        })
+    }
+
+    // Rebuilds the pipeline after the render target state changed
+    pub(crate) fn rebuild_pipeline(&mut self, device: &wgpu::Device, settings: &PipelineSettings) {
+        self.pipeline = Self::create_pipeline(
+            device,
+            &self.desc,
+            &self.pipeline_layout,
+            &self.shader,
+            settings,
+        );
+    }
+
+    pub(crate) fn blend(&self) -> BlendMode {
+        self.desc.blend
+    }
+
+    pub(crate) fn vertex_layout(&self) -> &wgpu::VertexBufferLayout<'static> {
+        &self.desc.vertex_layout
+    }
+
+    pub(crate) fn path(&self) -> Option<&Path> {
+        self.desc.path.as_deref()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn source(&self) -> &str {
+        &self.desc.source
+    }
+
+    // Recompiles the shader from the given file. On failure the error
+    // is returned and the previous shader and pipeline are kept.
+    pub(crate) fn reload_shader(
+        &mut self,
+        device: &wgpu::Device,
+        path: &Path,
+        settings: &PipelineSettings,
+    ) -> Result<()> {
+        let source = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read shader {}", path.display()))?;
+
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let shader = Self::create_shader(device, &self.desc.label, &source);
+        let pipeline =
+            Self::create_pipeline(device, &self.desc, &self.pipeline_layout, &shader, settings);
+        if let Some(message) = Self::pop_validation_error(device) {
+            bail!("Failed to reload material {}: {message}", self.desc.label);
+        }
+
+        self.desc.source = source;
+        self.desc.path = Some(path.to_path_buf());
+        self.shader = shader;
+        self.pipeline = pipeline;
+        Ok(())
+    }
+
+    pub(crate) fn write_uniforms(&self, queue: &wgpu::Queue, bytes: &[u8]) -> Result<()> {
+        let Some(buffer) = &self.uniform_buffer else {
+            bail!("Material {} has no uniforms", self.desc.label);
+        };
+        if bytes.len() as u64 > buffer.size() {
+            bail!(
+                "Material {} has room for {} bytes of uniforms, got {}",
+                self.desc.label,
+                buffer.size(),
+                bytes.len()
+            );
+        }
+        // write_buffer panics on sizes that are not a multiple of 4
+        if !(bytes.len() as u64).is_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT) {
+            bail!(
+                "Material {} uniforms must be a multiple of {} bytes, got {}",
+                self.desc.label,
+                wgpu::COPY_BUFFER_ALIGNMENT,
+                bytes.len()
+            );
+        }
+        queue.write_buffer(buffer, 0, bytes);
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+//! Bine renderer
+//!
+//! Author: BEKs => 18.10.2026
+//!
+//! GPU meshes uploaded by the game and the handles used to draw them
+
+use std::ops::Range;
+
+use wgpu::util::DeviceExt;
+
+use super::VertexLayout;
+
+// === MeshHandle struct
+// Returned from Renderer::upload_mesh and used to refer to the
+// mesh when submitting draws.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MeshHandle(pub(crate) u32);
+
+// === Indices enum
+// Index data of a mesh, 16 bit indices take half the memory of 32 bit
+// ones but can only address 65536 vertices. Without indices every
+// three vertices in order make a triangle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Indices<'a> {
+    U16(&'a [u16]),
+    U32(&'a [u32]),
+    None,
+}
+
+impl<'a> From<&'a [u16]> for Indices<'a> {
+    fn from(indices: &'a [u16]) -> Self {
+        Self::U16(indices)
+    }
+}
+
+impl<'a> From<&'a [u32]> for Indices<'a> {
+    fn from(indices: &'a [u32]) -> Self {
+        Self::U32(indices)
+    }
+}
+
+impl<'a, const N: usize> From<&'a [u16; N]> for Indices<'a> {
+    fn from(indices: &'a [u16; N]) -> Self {
+        Self::U16(indices)
+    }
+}
+
+impl<'a, const N: usize> From<&'a [u32; N]> for Indices<'a> {
+    fn from(indices: &'a [u32; N]) -> Self {
+        Self::U32(indices)
+    }
+}
+
+impl<'a> From<&'a Vec<u16>> for Indices<'a> {
+    fn from(indices: &'a Vec<u16>) -> Self {
+        Self::U16(indices)
+    }
+}
+
+impl<'a> From<&'a Vec<u32>> for Indices<'a> {
+    fn from(indices: &'a Vec<u32>) -> Self {
+        Self::U32(indices)
+    }
+}
+
+// === Mesh struct
+pub(crate) struct Mesh {
+    pub(crate) vertex_buffer: wgpu::Buffer,
+    // None for meshes drawn without indices
+    pub(crate) index_buffer: Option<(wgpu::Buffer, wgpu::IndexFormat)>,
+    // number of indices, or of vertices without indices
+    pub(crate) num_elements: u32,
+    // only materials built for the same layout can draw the mesh
+    pub(crate) vertex_layout: wgpu::VertexBufferLayout<'static>,
+}
+
+impl Mesh {
+    pub(crate) fn new<V: VertexLayout>(
+        device: &wgpu::Device,
+        vertices: &[V],
+        indices: Indices,
+    ) -> Self {
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Vertex Buffer"),
+            contents: bytemuck::cast_slice(vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
+        let (contents, format, num_elements): (&[u8], _, _) = match indices {
+            Indices::U16(indices) => (
+                bytemuck::cast_slice(indices),
+                wgpu::IndexFormat::Uint16,
+                indices.len(),
+            ),
+            Indices::U32(indices) => (
+                bytemuck::cast_slice(indices),
+                wgpu::IndexFormat::Uint32,
+                indices.len(),
+            ),
+            Indices::None => (&[], wgpu::IndexFormat::Uint32, vertices.len()),
+        };
+        let index_buffer = (indices != Indices::None).then(|| {
+            let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Index Buffer"),
+                contents,
+                usage: wgpu::BufferUsages::INDEX,
+            });
+            (buffer, format)
+        });
+
+        Self {
+            vertex_buffer,
+            index_buffer,
+            num_elements: num_elements as u32,
+            vertex_layout: V::desc(),
+        }
+    }
+
+    pub(crate) fn draw(&self, render_pass: &mut wgpu::RenderPass, instances: Range<u32>) {
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        match &self.index_buffer {
+            Some((buffer, format)) => {
+                render_pass.set_index_buffer(buffer.slice(..), *format);
+                render_pass.draw_indexed(0..self.num_elements, 0, instances);
+            }
+            None => render_pass.draw(0..self.num_elements, instances),
+        }
+    }
+}
@@ -1,11 +1,72 @@
+pub mod adapter;
+pub mod animation;
+pub mod atlas;
+pub mod blend;
+pub mod builder;
 pub mod camera;
+pub mod camera2d;
+pub mod debug_draw;
+mod debug_renderer;
+pub mod depth;
+mod draw;
+pub mod errors;
+mod hot_reload;
+pub mod instance;
+pub mod light;
+pub mod material;
+pub mod mesh;
+mod mipmap;
+mod registry;
 pub mod renderer;
+pub mod sprite;
+mod sprite_renderer;
+mod target;
+pub mod text;
+mod text_renderer;
 pub mod texture;
 pub mod vertex;
 
+pub use adapter::AdapterDetails;
+pub use animation::AnimationFrame;
+pub use animation::PlaybackMode;
+pub use animation::SpriteAnimation;
+pub use atlas::AtlasBuilder;
+pub use atlas::TextureAtlas;
+pub use blend::BlendMode;
+pub use builder::RendererBuilder;
 pub use camera::Camera;
 pub use camera::CameraUniform;
+pub use camera::Projection;
+pub use camera2d::Camera2D;
+pub use debug_draw::DebugDraw;
+pub use depth::DepthConfig;
+pub use errors::AdapterNotFound;
+pub use errors::RenderError;
+pub use instance::Instance;
+pub use light::Attenuation;
+pub use light::DirectionalLight;
+pub use light::Light;
+pub use light::LightHandle;
+pub use light::LitMaterial;
+pub use light::PointLight;
+pub use light::SpotLight;
+pub use material::MaterialDescriptor;
+pub use material::MaterialHandle;
+pub use mesh::Indices;
+pub use mesh::MeshHandle;
 pub use renderer::Renderer;
 pub use renderer::RendererBackends;
+pub use sprite::Rect;
+pub use sprite::SpriteBatch;
+pub use text::FontHandle;
+pub use text::TextAlign;
+pub use text::TextOptions;
 pub use texture::Texture;
+pub use texture::TextureHandle;
+pub use texture::TextureOptions;
+pub use vertex::ColorVertex;
+pub use vertex::NormalVertex;
+pub use vertex::PbrVertex;
 pub use vertex::Vertex;
+pub use vertex::VertexLayout;
+pub use wgpu::PresentMode;
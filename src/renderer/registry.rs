@@ -0,0 +1,55 @@
+//! Bine renderer
+//!
+//! Author: BEKs => 18.10.2026
+//!
+//! Storage for GPU resources that are handed out to the game as handles
+
+use std::collections::HashMap;
+
+// === Registry struct
+// Ids are never reused, so a stale handle can not point
+// at a resource that was uploaded after it was removed.
+pub(crate) struct Registry<T> {
+    items: HashMap<u32, T>,
+    next_id: u32,
+}
+
+impl<T> Registry<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            items: HashMap::new(),
+            next_id: 0,
+        }
+    }
+
+    pub(crate) fn insert(&mut self, item: T) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.items.insert(id, item);
+        id
+    }
+
+    pub(crate) fn get(&self, id: u32) -> Option<&T> {
+        self.items.get(&id)
+    }
+
+    pub(crate) fn get_mut(&mut self, id: u32) -> Option<&mut T> {
+        self.items.get_mut(&id)
+    }
+
+    pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = (u32, &mut T)> {
+        self.items.iter_mut().map(|(id, item)| (*id, item))
+    }
+
+    pub(crate) fn values(&self) -> impl Iterator<Item = &T> {
+        self.items.values()
+    }
+
+    pub(crate) fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.items.values_mut()
+    }
+
+    pub(crate) fn remove(&mut self, id: u32) -> Option<T> {
+        self.items.remove(&id)
+    }
+}
@@ -4,22 +4,51 @@
 //!
 //! This renderer module is tied to wgpu library
 
-use std::{fs, num, path::Path, vec};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, mpsc},
+};
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, anyhow, bail};
+use bytemuck::Pod;
 
-use cgmath::{Point3, Vector3};
+use cgmath::{Matrix4, Point3, Vector2, Vector3};
 use wgpu::{
-    Backends, Color, DeviceDescriptor, ExperimentalFeatures, Features, FragmentState, Instance,
-    InstanceDescriptor, Limits, PipelineLayoutDescriptor, PowerPreference,
-    RenderPassColorAttachment, RenderPassDescriptor, RenderPipelineDescriptor,
-    RequestAdapterOptions, SurfaceConfiguration, SurfaceTargetUnsafe, TextureUsages, Trace,
-    VertexState, include_wgsl,
+    Backend, Color, Features, PresentMode, RenderPassColorAttachment,
+    RenderPassDepthStencilAttachment, RenderPassDescriptor,
     util::DeviceExt,
-    wgt::{CommandEncoderDescriptor, TextureViewDescriptor},
+    wgt::{COPY_BYTES_PER_ROW_ALIGNMENT, CommandEncoderDescriptor},
 };
 
-use super::{Texture, Vertex};
+use image::{DynamicImage, RgbaImage};
+
+use super::{
+    AdapterDetails, AtlasBuilder, BlendMode, DebugDraw, DepthConfig, FontHandle, Instance,
+    MeshHandle, RenderError, RendererBuilder, TextOptions, Texture, TextureAtlas, TextureHandle,
+    TextureOptions, VertexLayout, adapter,
+    debug_renderer::DebugRenderer,
+    draw::DrawList,
+    hot_reload::ShaderWatcher,
+    instance::InstanceRaw,
+    light::{
+        DirectionalLight, Light, LightHandle, LitMaterial, MAX_POINT_LIGHTS, MAX_SPOT_LIGHTS,
+        SceneLights,
+    },
+    material::{
+        CAMERA_BIND_GROUP, MATERIAL_BIND_GROUP, Material, MaterialDescriptor, MaterialHandle,
+        PipelineSettings, SharedLayouts, TEXTURE_BIND_GROUP,
+    },
+    mesh::{Indices, Mesh},
+    mipmap::MipmapCache,
+    registry::Registry,
+    sprite::SpriteBatch,
+    sprite_renderer::SpriteRenderer,
+    target::{Frame, RenderTarget},
+    text::TextLayout,
+    text_renderer::TextRenderer,
+    texture::{LoadedTexture, TextureRegistry},
+};
 use crate::renderer::{Camera, CameraUniform};
 use winit::window::Window;
 
@@ -27,100 +56,190 @@ use winit::window::Window;
 pub struct Renderer {
     // wgpu specific internals
     instance: wgpu::Instance,
-    surface: wgpu::Surface<'static>,
+    adapter: wgpu::Adapter,
     device: wgpu::Device,
     queue: wgpu::Queue,
-    config: wgpu::SurfaceConfiguration,
+    target: RenderTarget,
+    // set by wgpu when the device is lost, checked every frame
+    device_lost: Arc<Mutex<Option<String>>>,
 
     // pipeline internals
-    pipeline: wgpu::RenderPipeline,
+    materials: Registry<Material>,
+    default_material: MaterialHandle,
+    // the built-in shader for each blend mode it was requested with
+    // keyed by blend mode and whether back faces are drawn
+    basic_materials: HashMap<(BlendMode, bool), MaterialHandle>,
+    // the same for the lit shader
+    lit_materials: HashMap<BlendMode, MaterialHandle>,
+    shader_watcher: Option<ShaderWatcher>,
     size: (u32, u32),
 
+    // depth buffer
+    depth_config: DepthConfig,
+    depth_texture: Option<Texture>,
+
+    // multisampling, the color attachment is resolved into the frame
+    sample_count: u32,
+    msaa_view: Option<wgpu::TextureView>,
+
     texture_bind_group_layout: wgpu::BindGroupLayout,
     camera_bind_group_layout: wgpu::BindGroupLayout,
 
     // camera
     camera: Option<Camera>,
+    auto_aspect: bool,
     camera_uniform: Option<CameraUniform>,
     camera_buffer: Option<wgpu::Buffer>,
     camera_bind_group: Option<wgpu::BindGroup>,
+    lights: SceneLights,
+
+    // resources uploaded by the game
+    meshes: Registry<Mesh>,
+    textures: TextureRegistry,
+    mipmaps: MipmapCache,
 
-    vertex_buffer: Option<wgpu::Buffer>,
-    index_buffer: Option<wgpu::Buffer>,
-    diffuse_texture: Option<Texture>,
-    diffuse_bind_group: Option<wgpu::BindGroup>,
-    num_indices: Option<u32>,
+    // draws submitted for the current frame
+    draw_list: DrawList,
+    instance_buffer: wgpu::Buffer,
+    instance_capacity: usize,
+    sprites: SpriteRenderer,
+    debug: DebugRenderer,
+    fonts: Registry<fontdue::Font>,
+    text: TextRenderer,
 }
 
-impl Renderer {
-    //TODO: Builder pattern could be used to make this Renderer construction
-    //      more idiomatic. But I do not have time for that now.
-    pub async fn new(window: &Window, backend: RendererBackends) -> Result<Self> {
-        let size = window.inner_size();
-        let bd = match backend {
-            RendererBackends::OpenGL => Backends::GL,
-            RendererBackends::Dx12 => Backends::DX12,
-            RendererBackends::Metal => Backends::METAL,
-            RendererBackends::Vulkan => Backends::VULKAN,
-            RendererBackends::BrowserWebGL => Backends::BROWSER_WEBGPU,
-            _ => Backends::NOOP,
-        };
+// === RuntimeSettings struct
+// Everything the game can change on a running renderer that is not a
+// GPU resource. Taken from a renderer whose device was lost and put on
+// the one that replaces it, so only uploaded resources have to be
+// created again.
+pub(crate) struct RuntimeSettings {
+    present_mode: Option<PresentMode>,
+    sample_count: u32,
+    depth_config: DepthConfig,
+    auto_aspect: bool,
+    camera: Option<Camera>,
+    ambient: [f32; 3],
+    directional: Option<DirectionalLight>,
+    lights: Registry<Light>,
+    shader_dir: Option<PathBuf>,
+}
 
-        let instance = Instance::new(&InstanceDescriptor {
-            backends: bd,
-            ..Default::default()
-        });
+impl Renderer {
+    const INITIAL_INSTANCE_CAPACITY: usize = 64;
+    const BASIC_SHADER_FILE: &str = "basic.wgsl";
+    const LIT_SHADER_FILE: &str = "lit.wgsl";
 
-        // ===
-        // This hack is to be investigated later
-        let surface = unsafe {
-            let target =
-                SurfaceTargetUnsafe::from_window(window).expect("Failed to create unsafe surface");
-            instance
-                .create_surface_unsafe(target)
-                .expect("failed to create unsafe surface with unsafe target")
-        };
+    // Creates a renderer with the default settings, see RendererBuilder
+    // for everything else. vsync picks a present mode that waits for the
+    // display, without it frames are presented as fast as possible.
+    pub async fn new(window: &Window, backend: RendererBackends, vsync: bool) -> Result<Self> {
+        RendererBuilder::new()
+            .backend(backend)
+            .vsync(vsync)
+            .build(window)
+            .await
+    }
 
-        let adapter = instance
-            .request_adapter(&RequestAdapterOptions {
-                power_preference: PowerPreference::default(),
-                compatible_surface: Some(&surface),
-                force_fallback_adapter: false,
-            })
+    // Creates the renderer on an adapter picked from enumerate_adapters(),
+    // e.g. from a GPU selection in the options menu
+    pub async fn with_adapter(
+        window: &Window,
+        adapter: &AdapterDetails,
+        vsync: bool,
+    ) -> Result<Self> {
+        RendererBuilder::new()
+            .adapter(adapter.clone())
+            .vsync(vsync)
+            .build(window)
             .await
-            .context("Failed to create adapter")?;
-
-        let (device, queue) = adapter
-            .request_device(&DeviceDescriptor {
-                label: None,
-                required_features: Features::ADDRESS_MODE_CLAMP_TO_BORDER,
-                experimental_features: ExperimentalFeatures::disabled(),
-                required_limits: Limits::defaults(),
-                memory_hints: Default::default(),
-                trace: Trace::Off,
-            })
+    }
+
+    // Takes the runtime settings out of a renderer that is about to be
+    // replaced, e.g. after its device was lost
+    pub(crate) fn into_runtime_settings(mut self) -> RuntimeSettings {
+        RuntimeSettings {
+            present_mode: self.present_mode(),
+            sample_count: self.sample_count,
+            depth_config: self.depth_config,
+            auto_aspect: self.auto_aspect,
+            camera: self.camera,
+            ambient: self.lights.ambient,
+            directional: self.lights.directional,
+            lights: std::mem::replace(&mut self.lights.lights, Registry::new()),
+            shader_dir: self
+                .shader_watcher
+                .as_ref()
+                .map(|watcher| watcher.shader_dir().to_path_buf()),
+        }
+    }
+
+    // Puts the settings of the replaced renderer on this one. Light
+    // handles stay valid. A sample count the new adapter does not
+    // support is reported and the built one kept.
+    pub(crate) fn apply_runtime_settings(&mut self, settings: RuntimeSettings) {
+        if let Some(mode) = settings.present_mode {
+            self.set_present_mode(mode);
+        }
+        if let Err(e) = self.set_sample_count(settings.sample_count) {
+            log::error!("{e:#}");
+        }
+        self.set_depth_config(settings.depth_config);
+        self.auto_aspect = settings.auto_aspect;
+        if let Some(camera) = settings.camera {
+            self.update_camera(&camera);
+        }
+        self.lights.ambient = settings.ambient;
+        self.lights.directional = settings.directional;
+        self.lights.lights = settings.lights;
+        if let Some(shader_dir) = settings.shader_dir {
+            self.enable_shader_hot_reload(shader_dir);
+        }
+    }
+
+    // Creates a renderer without a window. Frames are drawn into an
+    // offscreen texture which can be read back with read_pixels(),
+    // e.g. for golden-image tests on a machine with a software adapter.
+    pub async fn new_headless(width: u32, height: u32, backend: RendererBackends) -> Result<Self> {
+        RendererBuilder::new()
+            .backend(backend)
+            .build_headless(width, height)
             .await
-            .context("Failed to create device")?;
+    }
 
-        let surface_caps = surface.get_capabilities(&adapter);
-        let surface_format = surface_caps
-            .formats
-            .iter()
-            .find(|f| f.is_srgb())
-            .copied()
-            .unwrap_or(surface_caps.formats[0]);
-
-        let config = SurfaceConfiguration {
-            usage: TextureUsages::RENDER_ATTACHMENT,
-            format: surface_format,
-            width: size.width,
-            height: size.height,
-            present_mode: surface_caps.present_modes[0],
-            desired_maximum_frame_latency: 2,
-            alpha_mode: surface_caps.alpha_modes[0],
-            view_formats: vec![],
-        };
-        surface.configure(&device, &config);
+    pub fn builder() -> RendererBuilder {
+        RendererBuilder::new()
+    }
+
+    // Lists the adapters available on the given backends
+    pub fn enumerate_adapters(backend: RendererBackends) -> Vec<AdapterDetails> {
+        adapter::enumerate_adapters(backend)
+    }
+
+    // The features enabled on the device, the required ones plus the
+    // optional ones the adapter supports
+    pub fn granted_features(&self) -> Features {
+        self.device.features()
+    }
+
+    // Builds the pipeline and its layouts for the given render target
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn from_target(
+        instance: wgpu::Instance,
+        adapter: wgpu::Adapter,
+        device: wgpu::Device,
+        queue: wgpu::Queue,
+        target: RenderTarget,
+        size: (u32, u32),
+        sample_count: u32,
+        depth_config: DepthConfig,
+    ) -> Result<Self> {
+        let supported = Self::sample_counts(&adapter, &device, target.format());
+        if !supported.contains(&sample_count) {
+            bail!(
+                "Sample count {sample_count} is not supported, supported counts are {supported:?}"
+            );
+        }
 
         let texture_bind_group_layout =
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
@@ -147,96 +266,231 @@ impl Renderer {
 
         let camera_bind_group_layout =
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                entries: &[wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::VERTEX,
-                    ty: wgpu::BindingType::Buffer {
-                        ty: wgpu::BufferBindingType::Uniform,
-                        has_dynamic_offset: false,
-                        min_binding_size: None,
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
                     },
-                    count: None,
-                }],
+                    // lights, read by the lit material
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                ],
                 label: Some("camera_bind_group_layout"),
             });
 
-        let shader = device.create_shader_module(include_wgsl!("../../shaders/basic.wgsl"));
+        let instance_buffer =
+            Self::create_instance_buffer(&device, Self::INITIAL_INSTANCE_CAPACITY);
 
-        let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
-            label: Some("Basic Layout"),
-            bind_group_layouts: &[&texture_bind_group_layout, &camera_bind_group_layout],
-            push_constant_ranges: &[],
+        // destroying the device on drop also reports it as lost, only
+        // unexpected losses are recorded
+        let device_lost = Arc::new(Mutex::new(None));
+        let lost = Arc::clone(&device_lost);
+        device.set_device_lost_callback(move |reason, message| {
+            if reason != wgpu::DeviceLostReason::Destroyed {
+                *lost.lock().unwrap() = Some(message);
+            }
         });
 
-        let render_pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
-            label: Some("Shape Pipeline"),
-            layout: Some(&pipeline_layout),
-            vertex: VertexState {
-                module: &shader,
-                entry_point: Some("vs_main"),
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
-                buffers: &[Vertex::desc()],
+        let settings = PipelineSettings {
+            format: target.format(),
+            depth: depth_config,
+            sample_count,
+        };
+
+        // the built-in material everything is drawn with by default
+        let mut materials = Registry::new();
+        let default_material = Material::new(
+            &device,
+            MaterialDescriptor::new("Basic Material", include_str!("../../shaders/basic.wgsl")),
+            &SharedLayouts {
+                texture: &texture_bind_group_layout,
+                camera: &camera_bind_group_layout,
+            },
+            &[],
+            &settings,
+        )
+        .expect("Built-in basic shader failed to compile");
+        let default_material = MaterialHandle(materials.insert(default_material));
+
+        let sprites = SpriteRenderer::new(
+            &device,
+            &SharedLayouts {
+                texture: &texture_bind_group_layout,
+                camera: &camera_bind_group_layout,
             },
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: Some(wgpu::Face::Back),
-                polygon_mode: wgpu::PolygonMode::Fill,
-                unclipped_depth: false,
-                conservative: false,
+            &settings,
+        );
+
+        let debug = DebugRenderer::new(
+            &device,
+            &SharedLayouts {
+                texture: &texture_bind_group_layout,
+                camera: &camera_bind_group_layout,
             },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState {
-                count: 1,
-                mask: 0,
-                alpha_to_coverage_enabled: false,
+            &settings,
+        );
+
+        let lights = SceneLights::new(&device);
+        let text = TextRenderer::new(
+            &device,
+            &SharedLayouts {
+                texture: &texture_bind_group_layout,
+                camera: &camera_bind_group_layout,
             },
-            fragment: Some(FragmentState {
-                module: &shader,
-                entry_point: Some("fs_main"),
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: config.format,
-                    blend: Some(wgpu::BlendState::REPLACE),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-            }),
-            multiview: None,
-            cache: None,
-        });
+            &settings,
+            &lights.buffer,
+        );
 
-        Ok(Self {
-            surface: surface,
-            instance: instance,
-            device: device,
-            queue: queue,
-            config: config,
-            size: (size.width, size.height),
-            pipeline: render_pipeline,
-            vertex_buffer: None,
-            index_buffer: None,
-            num_indices: None,
-            diffuse_bind_group: None,
-            diffuse_texture: None,
+        let mut renderer = Self {
+            target,
+            device_lost,
+            instance,
+            adapter,
+            device,
+            queue,
+            size,
+            materials,
+            default_material,
+            basic_materials: HashMap::from([((BlendMode::Opaque, false), default_material)]),
+            lit_materials: HashMap::new(),
+            shader_watcher: None,
+            meshes: Registry::new(),
+            textures: TextureRegistry::new(),
+            mipmaps: MipmapCache::new(),
+            draw_list: DrawList::new(),
+            instance_buffer,
+            instance_capacity: Self::INITIAL_INSTANCE_CAPACITY,
+            sprites,
+            debug,
+            fonts: Registry::new(),
+            text,
             camera: None,
+            auto_aspect: true,
             camera_uniform: None,
             camera_buffer: None,
             camera_bind_group: None,
-            texture_bind_group_layout: texture_bind_group_layout,
-            camera_bind_group_layout: camera_bind_group_layout,
+            lights,
+            texture_bind_group_layout,
+            camera_bind_group_layout,
+            depth_config,
+            depth_texture: None,
+            sample_count,
+            msaa_view: None,
+        };
+        renderer.recreate_attachments();
+        Ok(renderer)
+    }
+
+    // Sample counts usable with the target format and the depth format.
+    // Beyond the counts WebGPU guarantees this needs the adapter
+    // specific format features.
+    fn sample_counts(
+        adapter: &wgpu::Adapter,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+    ) -> Vec<u32> {
+        let format_features = |format: wgpu::TextureFormat| {
+            if device
+                .features()
+                .contains(Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES)
+            {
+                adapter.get_texture_format_features(format).flags
+            } else {
+                format.guaranteed_format_features(device.features()).flags
+            }
+        };
+        let color = format_features(format);
+        let depth = format_features(Texture::DEPTH_FORMAT);
+        [1, 2, 4, 8]
+            .into_iter()
+            .filter(|&count| {
+                color.sample_count_supported(count) && depth.sample_count_supported(count)
+            })
+            .collect()
+    }
+
+    // Recreates the depth texture and multisampled color attachment
+    // for the current size, depth config and sample count
+    fn recreate_attachments(&mut self) {
+        self.depth_texture = self.depth_config.enabled.then(|| {
+            Texture::create_depth_texture(
+                &self.device,
+                self.size,
+                self.sample_count,
+                "depth_texture",
+            )
+        });
+        self.msaa_view = (self.sample_count > 1).then(|| {
+            self.target
+                .create_msaa_view(&self.device, self.size, self.sample_count)
+        });
+    }
+
+    fn create_instance_buffer(device: &wgpu::Device, capacity: usize) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Instance Buffer"),
+            size: (capacity * std::mem::size_of::<InstanceRaw>()) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
         })
     }
 
-    // Clearing the surface
-    // Basic necessity for rendering
-    pub fn render(&self, r: f64, g: f64, b: f64) {
-        let frame = self
-            .surface
-            .get_current_texture()
-            .expect("failed to retrieve frame");
+    // Uploads the instances of this frame's draw list,
+    // growing the instance buffer when it is too small.
+    fn write_instances(&mut self) {
+        let instances = self.draw_list.instances();
+
+        if instances.len() > self.instance_capacity {
+            self.instance_capacity = instances.len().next_power_of_two();
+            self.instance_buffer =
+                Self::create_instance_buffer(&self.device, self.instance_capacity);
+        }
+        self.queue
+            .write_buffer(&self.instance_buffer, 0, bytemuck::cast_slice(instances));
+    }
+
+    // Clears the surface and draws everything submitted with draw()
+    // since the last frame. The draw list is emptied afterwards.
+    // Lost or outdated surfaces are reconfigured and frames that time
+    // out are skipped, only unrecoverable errors are returned.
+    pub fn render(&mut self, r: f64, g: f64, b: f64) -> Result<(), RenderError> {
+        if let Some(message) = self.device_lost.lock().unwrap().take() {
+            return Err(RenderError::DeviceLost(message));
+        }
+
+        self.reload_changed_shaders();
+        let Some(frame) = self.acquire_frame()? else {
+            self.draw_list.clear();
+            self.sprites.clear();
+            self.debug.clear();
+            self.text.clear();
+            return Ok(());
+        };
+        if let Some(camera) = &self.camera {
+            self.draw_list
+                .sort_transparent(camera.eye(), camera.target());
+        }
+        self.write_instances();
+        self.lights.write(&self.queue);
+        self.sprites
+            .prepare(&self.device, &self.queue, &self.textures);
+        self.debug.prepare(&self.device, &self.queue);
+        self.text.prepare(&self.device, &self.queue, self.size);
 
-        let view = frame.texture.create_view(&TextureViewDescriptor::default());
         let mut encoder = self
             .device
             .create_command_encoder(&CommandEncoderDescriptor {
@@ -247,104 +501,800 @@ impl Renderer {
             let mut render_pass = encoder.begin_render_pass(&RenderPassDescriptor {
                 label: Some("Clear render pass"),
                 color_attachments: &[Some(RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
+                    view: self.msaa_view.as_ref().unwrap_or(&frame.view),
+                    resolve_target: self.msaa_view.as_ref().map(|_| &frame.view),
                     ops: wgpu::Operations {
                         load: wgpu::LoadOp::Clear(Color {
-                            r: r,
-                            g: g,
-                            b: b,
+                            r,
+                            g,
+                            b,
                             a: 0.5, // default at this point
                         }),
-                        store: wgpu::StoreOp::Store,
+                        // only the resolved frame is kept when multisampling
+                        store: if self.msaa_view.is_some() {
+                            wgpu::StoreOp::Discard
+                        } else {
+                            wgpu::StoreOp::Store
+                        },
                     },
 
                     depth_slice: None,
                 })],
-                depth_stencil_attachment: None,
+                depth_stencil_attachment: self.depth_texture.as_ref().map(|depth_texture| {
+                    RenderPassDepthStencilAttachment {
+                        view: &depth_texture.view,
+                        depth_ops: Some(wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(self.depth_config.clear_value),
+                            store: wgpu::StoreOp::Store,
+                        }),
+                        stencil_ops: None,
+                    }
+                }),
                 timestamp_writes: None,
                 occlusion_query_set: None,
             });
-            render_pass.set_pipeline(&self.pipeline);
-            if let Some(diffuse_bind_group) = &self.diffuse_bind_group {
-                render_pass.set_bind_group(0, diffuse_bind_group, &[]);
-            }
+            // nothing can be drawn before the game has set up a camera
             if let Some(camera_bind_group) = &self.camera_bind_group {
-                render_pass.set_bind_group(1, camera_bind_group, &[]);
-            }
-            if let Some(vertex_buffer) = &self.vertex_buffer {
-                render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
-            }
-            if let Some(index_buffer) = &self.index_buffer {
-                render_pass.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint16);
-            }
-            if let Some(num_indices) = self.num_indices {
-                render_pass.draw_indexed(0..num_indices, 0, 0..1);
+                render_pass.set_bind_group(CAMERA_BIND_GROUP, camera_bind_group, &[]);
+                render_pass.set_vertex_buffer(1, self.instance_buffer.slice(..));
+
+                let mut current_material = None;
+                for item in self.draw_list.items() {
+                    // draws referring to unloaded resources are skipped
+                    let (Some(material), Some(mesh), Some(texture)) = (
+                        self.materials.get(item.material.0),
+                        self.meshes.get(item.mesh.0),
+                        self.textures.get(item.texture),
+                    ) else {
+                        continue;
+                    };
+                    // as are meshes the material's pipeline can not read
+                    if mesh.vertex_layout != *material.vertex_layout() {
+                        continue;
+                    }
+                    if current_material != Some(item.material) {
+                        render_pass.set_pipeline(&material.pipeline);
+                        if let Some(bind_group) = &material.bind_group {
+                            render_pass.set_bind_group(MATERIAL_BIND_GROUP, bind_group, &[]);
+                        }
+                        current_material = Some(item.material);
+                    }
+                    render_pass.set_bind_group(TEXTURE_BIND_GROUP, &texture.bind_group, &[]);
+                    mesh.draw(&mut render_pass, item.instances.clone());
+                }
+
+                // sprites go on top of the scene
+                self.sprites.draw(&mut render_pass, &self.textures);
+                // then debug lines
+                self.debug.draw(&mut render_pass, camera_bind_group);
             }
+            // text is in screen space and needs no camera of the game
+            self.text.draw(&mut render_pass);
         } // drop render_pass so we can use encoder again
 
         self.queue.submit(std::iter::once(encoder.finish()));
         frame.present();
+        self.draw_list.clear();
+        self.sprites.clear();
+        self.debug.clear();
+        self.text.clear();
+        Ok(())
     }
 
-    // React to changes in window size
+    // Acquires the next frame, None means the frame should be skipped
+    fn acquire_frame(&mut self) -> Result<Option<Frame>, RenderError> {
+        match self.target.acquire() {
+            Ok(frame) => Ok(Some(frame)),
+            Err(wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated) => {
+                // reconfigure the surface and try once more
+                let (width, height) = self.size;
+                self.target.resize(&self.device, width, height);
+                match self.target.acquire() {
+                    Ok(frame) => Ok(Some(frame)),
+                    Err(wgpu::SurfaceError::OutOfMemory) => Err(RenderError::OutOfMemory),
+                    Err(_) => Ok(None),
+                }
+            }
+            Err(wgpu::SurfaceError::Timeout) => Ok(None),
+            Err(wgpu::SurfaceError::OutOfMemory) => Err(RenderError::OutOfMemory),
+            Err(error) => Err(RenderError::SurfaceError(error.to_string())),
+        }
+    }
+
+    // Queues a mesh to be drawn with the given texture and model
+    // transform in the next call to render()
+    pub fn draw(&mut self, mesh: MeshHandle, texture: TextureHandle, transform: Matrix4<f32>) {
+        self.draw_with_material(
+            self.default_material,
+            mesh,
+            texture,
+            &[Instance::new(transform)],
+        );
+    }
+
+    // Queues many copies of a mesh, each with its own transform and
+    // tint, which are drawn with a single instanced draw call.
+    pub fn draw_instanced(
+        &mut self,
+        mesh: MeshHandle,
+        texture: TextureHandle,
+        instances: &[Instance],
+    ) {
+        self.draw_with_material(self.default_material, mesh, texture, instances);
+    }
+
+    // Same as draw_instanced() but using a material created with
+    // create_material() instead of the built-in one
+    pub fn draw_with_material(
+        &mut self,
+        material: MaterialHandle,
+        mesh: MeshHandle,
+        texture: TextureHandle,
+        instances: &[Instance],
+    ) {
+        let transparent = self
+            .materials
+            .get(material.0)
+            .is_some_and(|material| material.blend().is_transparent());
+        self.draw_list.push(
+            material,
+            mesh,
+            texture,
+            instances.iter().map(|i| i.to_raw()),
+            transparent,
+        );
+    }
+
+    // Queues every sprite of the batch for the next call to render()
+    // and empties the batch so it can be filled again next frame.
+    // Sprites are drawn after the meshes, on top of them.
+    pub fn draw_sprite_batch(&mut self, batch: &mut SpriteBatch) {
+        self.sprites.push(batch.sprites.drain(..));
+    }
+
+    // Collects debug lines, boxes and gizmos for the next call to
+    // render(). They are drawn on top of everything and cleared
+    // once the frame is rendered.
+    pub fn debug_draw(&mut self) -> &mut DebugDraw {
+        &mut self.debug.lines
+    }
+
+    // Loads a TrueType or OpenType font. The returned handle is used
+    // to draw text with the font.
+    pub fn load_font(&mut self, bytes: &[u8]) -> Result<FontHandle> {
+        let font = fontdue::Font::from_bytes(bytes, fontdue::FontSettings::default())
+            .map_err(|error| anyhow!("Failed to load font: {error}"))?;
+        Ok(FontHandle(self.fonts.insert(font)))
+    }
+
+    // Frees the font. Returns false if the handle was not loaded.
+    pub fn unload_font(&mut self, font: FontHandle) -> bool {
+        self.fonts.remove(font.0).is_some()
+    }
+
+    // Queues text for the next call to render(). The position is the
+    // top left corner of the text in screen pixels, size the height
+    // of the font in pixels.
+    pub fn draw_text(
+        &mut self,
+        font: FontHandle,
+        text: &str,
+        position: Vector2<f32>,
+        size: f32,
+        color: [f32; 4],
+    ) {
+        self.draw_text_with_options(font, text, position, &TextOptions::new(size, color));
+    }
+
+    // Same as draw_text() with alignment, wrapping and line spacing.
+    // Text drawn with an unloaded font is skipped.
+    pub fn draw_text_with_options(
+        &mut self,
+        font: FontHandle,
+        text: &str,
+        position: Vector2<f32>,
+        options: &TextOptions,
+    ) {
+        let Some(loaded) = self.fonts.get(font.0) else {
+            return;
+        };
+        let layout = TextLayout::new(loaded, text, position, options);
+        self.text
+            .push(font, loaded, &layout, options.size, options.color);
+    }
+
+    // Width and height in pixels the text would take up when drawn
+    pub fn measure_text(
+        &self,
+        font: FontHandle,
+        text: &str,
+        options: &TextOptions,
+    ) -> Vector2<f32> {
+        self.fonts
+            .get(font.0)
+            .map(|loaded| TextLayout::new(loaded, text, Vector2::new(0.0, 0.0), options).size)
+            .unwrap_or(Vector2::new(0.0, 0.0))
+    }
+
+    // Registers a material built from the game's own WGSL shader. See the
+    // material module for the bind groups and vertex inputs it can use.
+    pub fn create_material(&mut self, desc: MaterialDescriptor) -> Result<MaterialHandle> {
+        let mut textures = Vec::with_capacity(desc.textures.len());
+        for handle in &desc.textures {
+            let loaded = self
+                .textures
+                .get(*handle)
+                .with_context(|| format!("Material {} uses unloaded {handle:?}", desc.label))?;
+            textures.push(&loaded.texture);
+        }
+
+        let material = Material::new(
+            &self.device,
+            desc,
+            &SharedLayouts {
+                texture: &self.texture_bind_group_layout,
+                camera: &self.camera_bind_group_layout,
+            },
+            &textures,
+            &self.pipeline_settings(),
+        )?;
+        if let Some((watcher, path)) = self.shader_watcher.as_mut().zip(material.path()) {
+            watcher.watch(path);
+        }
+        Ok(MaterialHandle(self.materials.insert(material)))
+    }
+
+    // Development mode: the built-in shaders are loaded from shader_dir
+    // instead of the copies embedded in the binary, and every material
+    // backed by a file is recompiled when the file changes. A shader
+    // that fails to compile is reported and the previous one is kept.
+    pub fn enable_shader_hot_reload(&mut self, shader_dir: impl AsRef<Path>) {
+        let mut watcher = ShaderWatcher::new(shader_dir.as_ref());
+        let settings = self.pipeline_settings();
+
+        for (handle, path) in self.built_in_shader_files(watcher.shader_dir()) {
+            if let Some(material) = self.materials.get_mut(handle.0)
+                && let Err(e) = material.reload_shader(&self.device, &path, &settings)
+            {
+                log::error!("{e:#}");
+            }
+        }
+
+        for path in self.materials.values().filter_map(Material::path) {
+            watcher.watch(path);
+        }
+        // the built-in shaders are watched even if they failed to
+        // load, so fixing the file picks them up
+        for file in [Self::BASIC_SHADER_FILE, Self::LIT_SHADER_FILE] {
+            let path = watcher.shader_dir().join(file);
+            watcher.watch(&path);
+        }
+        self.shader_watcher = Some(watcher);
+    }
+
+    pub fn disable_shader_hot_reload(&mut self) {
+        self.shader_watcher = None;
+    }
+
+    // Recompiles the materials whose shader file changed on disk
+    fn reload_changed_shaders(&mut self) {
+        let Some(watcher) = self.shader_watcher.as_mut() else {
+            return;
+        };
+        let changed = watcher.changed_files();
+        if changed.is_empty() {
+            return;
+        }
+
+        let shader_dir = watcher.shader_dir().to_path_buf();
+        let built_in = self.built_in_shader_files(&shader_dir);
+        let settings = self.pipeline_settings();
+        for (id, material) in self.materials.iter_mut() {
+            let Some(path) = Self::hot_reload_path(MaterialHandle(id), material.path(), &built_in)
+            else {
+                continue;
+            };
+            if !changed.contains(&path) {
+                continue;
+            }
+            match material.reload_shader(&self.device, &path, &settings) {
+                Ok(()) => log::info!("Reloaded shader {}", path.display()),
+                Err(e) => log::error!("{e:#}"),
+            }
+        }
+    }
+
+    // The built-in materials and the file in the shader directory
+    // each of them is reloaded from
+    fn built_in_shader_files(&self, shader_dir: &Path) -> Vec<(MaterialHandle, PathBuf)> {
+        let basic = shader_dir.join(Self::BASIC_SHADER_FILE);
+        let lit = shader_dir.join(Self::LIT_SHADER_FILE);
+        self.basic_materials
+            .values()
+            .map(|&handle| (handle, basic.clone()))
+            .chain(
+                self.lit_materials
+                    .values()
+                    .map(|&handle| (handle, lit.clone())),
+            )
+            .collect()
+    }
+
+    // Built-in materials follow the shader directory even when loading
+    // from it failed, the others are reloaded from their own file
+    fn hot_reload_path(
+        handle: MaterialHandle,
+        material_path: Option<&Path>,
+        built_in: &[(MaterialHandle, PathBuf)],
+    ) -> Option<PathBuf> {
+        built_in
+            .iter()
+            .find(|(built_in, _)| *built_in == handle)
+            .map(|(_, path)| path.clone())
+            .or_else(|| material_path.map(Path::to_path_buf))
+    }
+
+    // The material used by draw() and draw_instanced()
+    pub fn default_material(&self) -> MaterialHandle {
+        self.default_material
+    }
+
+    // The built-in material drawn with the given blend mode, e.g.
+    // BlendMode::Alpha for textures with transparent parts. Each mode
+    // is created the first time it is asked for.
+    pub fn basic_material(&mut self, blend: BlendMode) -> MaterialHandle {
+        self.basic_material_variant(blend, false)
+    }
+
+    // Same as basic_material() without back face culling, for meshes
+    // that are seen from both sides like leaves or flat cards
+    pub fn double_sided_material(&mut self, blend: BlendMode) -> MaterialHandle {
+        self.basic_material_variant(blend, true)
+    }
+
+    fn basic_material_variant(&mut self, blend: BlendMode, double_sided: bool) -> MaterialHandle {
+        if let Some(handle) = self.basic_materials.get(&(blend, double_sided)) {
+            return *handle;
+        }
+
+        let sides = if double_sided { ", double sided" } else { "" };
+        let desc = MaterialDescriptor::new(
+            &format!("Basic Material ({blend:?}{sides})"),
+            include_str!("../../shaders/basic.wgsl"),
+        )
+        .with_blend(blend)
+        .with_cull_mode((!double_sided).then_some(wgpu::Face::Back));
+        let material = self.create_built_in_material(desc, Self::BASIC_SHADER_FILE);
+        self.basic_materials.insert((blend, double_sided), material);
+        material
+    }
+
+    // The built-in Blinn-Phong material lit by the lights of the
+    // renderer, with the default LitMaterial. Meshes drawn with it
+    // are uploaded with NormalVertex, e.g. MeshData::upload_with_normals.
+    // Other specular settings get their own material from
+    // create_material(LitMaterial::descriptor()).
+    pub fn lit_material(&mut self, blend: BlendMode) -> MaterialHandle {
+        if let Some(handle) = self.lit_materials.get(&blend) {
+            return *handle;
+        }
+
+        let desc = LitMaterial::default()
+            .descriptor(&format!("Lit Material ({blend:?})"))
+            .with_blend(blend);
+        let material = self.create_built_in_material(desc, Self::LIT_SHADER_FILE);
+        self.lit_materials.insert(blend, material);
+        material
+    }
+
+    fn create_built_in_material(&mut self, desc: MaterialDescriptor, file: &str) -> MaterialHandle {
+        let material = self
+            .create_material(desc)
+            .expect("Built-in shader failed to compile");
+
+        // pick up the shader file while developing with hot reload
+        if let Some(watcher) = &self.shader_watcher {
+            let path = watcher.shader_dir().join(file);
+            let settings = self.pipeline_settings();
+            if let Some(built_in) = self.materials.get_mut(material.0)
+                && let Err(e) = built_in.reload_shader(&self.device, &path, &settings)
+            {
+                log::error!("{e:#}");
+            }
+        }
+        material
+    }
+
+    // Sets the light every lit surface gets regardless of the other
+    // lights, as linear rgb. Defaults to a dim grey.
+    pub fn set_ambient_light(&mut self, color: [f32; 3]) {
+        self.lights.ambient = color;
+    }
+
+    // Sets or, with None, removes the directional light
+    pub fn set_directional_light(&mut self, light: Option<DirectionalLight>) {
+        self.lights.directional = light;
+    }
+
+    // Adds a point or spot light. At most MAX_POINT_LIGHTS point
+    // and MAX_SPOT_LIGHTS spot lights can exist at the same time.
+    pub fn add_light(&mut self, light: impl Into<Light>) -> Result<LightHandle> {
+        let light = light.into();
+        let (spot, max, kind) = match light {
+            Light::Point(_) => (false, MAX_POINT_LIGHTS, "point"),
+            Light::Spot(_) => (true, MAX_SPOT_LIGHTS, "spot"),
+        };
+        if self.lights.count(spot) >= max {
+            bail!("Can not add more than {max} {kind} lights");
+        }
+        Ok(LightHandle(self.lights.lights.insert(light)))
+    }
+
+    // The light to read or change, e.g. to move it every frame
+    pub fn light_mut(&mut self, handle: LightHandle) -> Option<&mut Light> {
+        self.lights.lights.get_mut(handle.0)
+    }
+
+    // Moves a point or spot light. Returns false if the handle
+    // was removed.
+    pub fn set_light_position(&mut self, handle: LightHandle, position: Point3<f32>) -> bool {
+        self.light_mut(handle)
+            .map(|light| light.set_position(position))
+            .is_some()
+    }
+
+    // Removes the light. Returns false if the handle was removed already.
+    pub fn remove_light(&mut self, handle: LightHandle) -> bool {
+        self.lights.lights.remove(handle.0).is_some()
+    }
+
+    // Overwrites the uniform buffer of a material, usually with
+    // the same type it was created with.
+    pub fn update_material<T: Pod>(
+        &mut self,
+        material: MaterialHandle,
+        uniforms: &T,
+    ) -> Result<()> {
+        self.materials
+            .get(material.0)
+            .with_context(|| format!("{material:?} does not exist"))?
+            .write_uniforms(&self.queue, bytemuck::bytes_of(uniforms))
+    }
+
+    // React to changes in window size. The surface (or offscreen
+    // texture), depth buffer and multisampled color attachment are
+    // recreated and the active camera picks up the new aspect ratio.
     pub fn resize(&mut self, width: u32, height: u32) {
         if width > 0 && height > 0 {
-            self.config.width = width;
-            self.config.height = height;
             self.size = (width, height);
-            self.surface.configure(&self.device, &self.config);
+            self.target.resize(&self.device, width, height);
+            self.recreate_attachments();
+            if let Some(camera) = self.camera {
+                self.update_camera(&camera);
+            }
         }
     }
 
-    // Provides access for game to register data to be used in
-    // renderer.
-    pub fn load_texture(&mut self, bytes: &[u8], file_name: &str) {
-        let diffuse_texture = Texture::from_bytes(&self.device, &self.queue, bytes, file_name)
-            .expect("Failed to load texture from bytes");
-
-        let texture_bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &self.texture_bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&diffuse_texture.view),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&diffuse_texture.sampler),
-                },
-            ],
-            label: Some("diffuse_bind_group"),
-        });
-        self.diffuse_texture = Some(diffuse_texture);
-        self.diffuse_bind_group = Some(texture_bind_group);
+    pub(crate) fn vsync_present_mode(vsync: bool) -> PresentMode {
+        if vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        }
     }
 
-    // Provides access for the game to register the indices and vertices for
-    // the textures that it provides
-    pub fn set_geometry(&mut self, vertices: &[Vertex], indices: &[u16]) {
-        let vertex_buffer = self
-            .device
-            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                label: Some("Vertex Buffer"),
-                contents: bytemuck::cast_slice(&vertices),
-                usage: wgpu::BufferUsages::VERTEX,
-            });
+    // The present mode the surface is configured with,
+    // None for headless renderers
+    pub fn present_mode(&self) -> Option<PresentMode> {
+        self.target.present_mode()
+    }
+
+    // Switches the present mode without recreating the renderer. Modes the
+    // surface does not support fall back to the closest one (Mailbox and
+    // Immediate to each other, everything else to Fifo). Returns the mode
+    // actually used, None for headless renderers.
+    pub fn set_present_mode(&mut self, mode: PresentMode) -> Option<PresentMode> {
+        self.target.set_present_mode(&self.device, mode)
+    }
+
+    pub fn set_vsync(&mut self, vsync: bool) -> Option<PresentMode> {
+        self.set_present_mode(Self::vsync_present_mode(vsync))
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    pub fn aspect_ratio(&self) -> f32 {
+        self.size.0 as f32 / self.size.1 as f32
+    }
+
+    // When on (the default) every camera given to the renderer uses the
+    // aspect ratio of the render target instead of its own.
+    pub fn set_auto_aspect(&mut self, auto_aspect: bool) {
+        self.auto_aspect = auto_aspect;
+    }
+
+    pub fn depth_config(&self) -> DepthConfig {
+        self.depth_config
+    }
+
+    // Changes how depth testing is done. The pipeline depends on the
+    // depth state, so it is rebuilt together with the depth texture.
+    pub fn set_depth_config(&mut self, depth_config: DepthConfig) {
+        if depth_config == self.depth_config {
+            return;
+        }
+        self.depth_config = depth_config;
+        self.recreate_attachments();
+        self.rebuild_pipelines();
+    }
+
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    // The MSAA sample counts the adapter supports for the render target
+    pub fn supported_sample_counts(&self) -> Vec<u32> {
+        Self::sample_counts(&self.adapter, &self.device, self.target.format())
+    }
+
+    // Switches MSAA on (2, 4 or 8 samples) or off (1 sample). The
+    // attachments and every pipeline are rebuilt for the new count.
+    pub fn set_sample_count(&mut self, sample_count: u32) -> Result<()> {
+        if sample_count == self.sample_count {
+            return Ok(());
+        }
+        let supported = self.supported_sample_counts();
+        if !supported.contains(&sample_count) {
+            bail!(
+                "Sample count {sample_count} is not supported, supported counts are {supported:?}"
+            );
+        }
+        self.sample_count = sample_count;
+        self.recreate_attachments();
+        self.rebuild_pipelines();
+        Ok(())
+    }
+
+    fn pipeline_settings(&self) -> PipelineSettings {
+        PipelineSettings {
+            format: self.target.format(),
+            depth: self.depth_config,
+            sample_count: self.sample_count,
+        }
+    }
+
+    // Recreates every material pipeline for the current target state
+    fn rebuild_pipelines(&mut self) {
+        let settings = self.pipeline_settings();
+        for material in self.materials.values_mut() {
+            material.rebuild_pipeline(&self.device, &settings);
+        }
+        self.sprites.rebuild_pipeline(&self.device, &settings);
+        self.debug.rebuild_pipeline(&self.device, &settings);
+        self.text.rebuild_pipeline(&self.device, &settings);
+    }
+
+    // Copies the last rendered frame back to the CPU. Only available
+    // for headless renderers, the swapchain images of a window surface
+    // cannot be read once presented.
+    pub fn read_pixels(&self) -> Result<RgbaImage> {
+        let RenderTarget::Offscreen { texture } = &self.target else {
+            bail!("Pixel readback is only supported by headless renderers");
+        };
+
+        let (width, height) = self.size;
+        let unpadded_bytes_per_row = 4 * width;
+        // rows in the copy destination have to be aligned to 256 bytes
+        let padded_bytes_per_row = unpadded_bytes_per_row.div_ceil(COPY_BYTES_PER_ROW_ALIGNMENT)
+            * COPY_BYTES_PER_ROW_ALIGNMENT;
 
-        let index_buffer = self
+        let readback_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Readback Buffer"),
+            size: (padded_bytes_per_row * height) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self
             .device
-            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                label: Some("Index Buffer"),
-                contents: bytemuck::cast_slice(&indices),
-                usage: wgpu::BufferUsages::INDEX,
+            .create_command_encoder(&CommandEncoderDescriptor {
+                label: Some("Readback Encoder"),
             });
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &readback_buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            texture.size(),
+        );
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = readback_buffer.slice(..);
+        let (sender, receiver) = mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device
+            .poll(wgpu::PollType::wait_indefinitely())
+            .context("Failed to wait for pixel readback")?;
+        receiver
+            .recv()
+            .context("Readback buffer was dropped before being mapped")?
+            .context("Failed to map readback buffer")?;
+
+        let pixels = {
+            let data = slice.get_mapped_range();
+            data.chunks(padded_bytes_per_row as usize)
+                .flat_map(|row| &row[..unpadded_bytes_per_row as usize])
+                .copied()
+                .collect::<Vec<u8>>()
+        };
+        readback_buffer.unmap();
+
+        RgbaImage::from_raw(width, height, pixels).context("Readback buffer has the wrong size")
+    }
+
+    fn create_texture_bind_group(&self, texture: &Texture) -> wgpu::BindGroup {
+        texture.create_bind_group(&self.device, &self.texture_bind_group_layout)
+    }
+
+    // Provides access for game to register data to be used in
+    // renderer. The returned handle is used to draw with the texture,
+    // and the name can be used to look the handle up later.
+    pub fn load_texture(&mut self, bytes: &[u8], name: &str) -> Result<TextureHandle> {
+        self.load_texture_with_options(bytes, name, TextureOptions::default())
+    }
+
+    // Same as load_texture() with control over filtering, wrapping,
+    // color space and mip generation
+    pub fn load_texture_with_options(
+        &mut self,
+        bytes: &[u8],
+        name: &str,
+        options: TextureOptions,
+    ) -> Result<TextureHandle> {
+        let diffuse_texture = Texture::from_bytes_cached(
+            &self.device,
+            &self.queue,
+            bytes,
+            name,
+            &options,
+            &mut self.mipmaps,
+        )
+        .with_context(|| format!("Failed to load texture {name}"))?;
+        Ok(self.insert_texture(name, diffuse_texture, options))
+    }
+
+    // Same as load_texture_with_options() for an already decoded image,
+    // e.g. one generated at runtime
+    pub fn load_texture_from_image(
+        &mut self,
+        image: &DynamicImage,
+        name: &str,
+        options: TextureOptions,
+    ) -> Result<TextureHandle> {
+        let diffuse_texture = Texture::from_image_cached(
+            &self.device,
+            &self.queue,
+            image,
+            Some(name),
+            &options,
+            &mut self.mipmaps,
+        )
+        .with_context(|| format!("Failed to load texture {name}"))?;
+        Ok(self.insert_texture(name, diffuse_texture, options))
+    }
+
+    fn insert_texture(
+        &mut self,
+        name: &str,
+        texture: Texture,
+        options: TextureOptions,
+    ) -> TextureHandle {
+        let bind_group = self.create_texture_bind_group(&texture);
+        self.textures.insert(
+            name,
+            LoadedTexture {
+                texture,
+                label: name.to_owned(),
+                options,
+                bind_group,
+            },
+        )
+    }
+
+    // Packs the images of the builder into one texture registered
+    // under the given name. Draw the images with the atlas texture and
+    // their regions as uv rects.
+    pub fn create_atlas(
+        &mut self,
+        builder: &AtlasBuilder,
+        name: &str,
+        options: TextureOptions,
+    ) -> Result<TextureAtlas> {
+        let (image, regions) = builder
+            .pack()
+            .with_context(|| format!("Failed to pack atlas {name}"))?;
+        let size = image.dimensions();
+        let texture =
+            self.load_texture_from_image(&DynamicImage::ImageRgba8(image), name, options)?;
+        Ok(TextureAtlas {
+            texture,
+            regions,
+            size,
+        })
+    }
 
-        let num_indices = indices.len() as u32;
+    // Looks up the handle of the texture last loaded with this name
+    pub fn find_texture(&self, name: &str) -> Option<TextureHandle> {
+        self.textures.find(name)
+    }
+
+    pub fn is_texture_loaded(&self, handle: TextureHandle) -> bool {
+        self.textures.get(handle).is_some()
+    }
 
-        self.num_indices = Some(num_indices);
-        self.index_buffer = Some(index_buffer);
-        self.vertex_buffer = Some(vertex_buffer);
+    // Uploads new image data for an already loaded texture. Draws
+    // using the handle pick up the new texture from the next frame.
+    // The options the texture was loaded with are kept.
+    pub fn replace_texture(&mut self, handle: TextureHandle, bytes: &[u8]) -> Result<()> {
+        let Some((label, options)) = self
+            .textures
+            .get(handle)
+            .map(|loaded| (loaded.label.clone(), loaded.options))
+        else {
+            bail!("Texture {handle:?} is not loaded");
+        };
+        let diffuse_texture = Texture::from_bytes_cached(
+            &self.device,
+            &self.queue,
+            bytes,
+            &label,
+            &options,
+            &mut self.mipmaps,
+        )
+        .with_context(|| format!("Failed to replace texture {label}"))?;
+        let bind_group = self.create_texture_bind_group(&diffuse_texture);
+
+        self.textures.replace(
+            handle,
+            LoadedTexture {
+                texture: diffuse_texture,
+                label,
+                options,
+                bind_group,
+            },
+        );
+        Ok(())
+    }
+
+    // Frees the texture. Returns false if the handle was not loaded.
+    // Draws still referring to it are skipped.
+    pub fn unload_texture(&mut self, handle: TextureHandle) -> bool {
+        self.textures.remove(handle).is_some()
+    }
+
+    // Provides access for the game to upload the vertices and indices
+    // of a mesh. The returned handle is used to draw the mesh with a
+    // material made for the vertex type, the basic one takes Vertex.
+    // Indices are u16 or u32 slices, or Indices::None to draw the
+    // vertices in order.
+    pub fn upload_mesh<'a, V: VertexLayout>(
+        &mut self,
+        vertices: &[V],
+        indices: impl Into<Indices<'a>>,
+    ) -> MeshHandle {
+        MeshHandle(
+            self.meshes
+                .insert(Mesh::new(&self.device, vertices, indices.into())),
+        )
     }
 
     // Provides access for the game to set the position of the camera
@@ -359,44 +1309,54 @@ impl Renderer {
         zfar: f32,
     ) {
         let camera = Camera::new(eye, target, up, aspect, fovy, znear, zfar);
-
-        let mut camera_uniform = CameraUniform::new();
-        camera_uniform.update_view_proj(&camera);
-
-        let camera_buffer = self
-            .device
-            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                label: Some("Camera Buffer"),
-                contents: bytemuck::cast_slice(&[camera_uniform]),
-                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-            });
-
-        let camera_bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("camera_bind_group"),
-            layout: &self.camera_bind_group_layout,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: camera_buffer.as_entire_binding(),
-            }],
-        });
-
-        self.camera = Some(camera);
-        self.camera_buffer = Some(camera_buffer);
-        self.camera_uniform = Some(camera_uniform);
-        self.camera_bind_group = Some(camera_bind_group);
+        self.update_camera(&camera);
     }
 
+    // Makes the camera the active one. Works for perspective and
+    // orthographic cameras alike, e.g. Camera2D::to_camera().
     pub fn update_camera(&mut self, camera: &Camera) {
-        if let Some(camera_uniform) = &mut self.camera_uniform {
-            camera_uniform.update_view_proj(camera);
+        let mut camera = *camera;
+        if self.auto_aspect {
+            camera.set_aspect(self.aspect_ratio());
         }
 
-        if let Some((camera_buffer, camera_uniform)) =
-            self.camera_buffer.as_ref().zip(self.camera_uniform)
-        {
+        let mut camera_uniform = self.camera_uniform.unwrap_or_else(CameraUniform::new);
+        camera_uniform.update_view_proj(&camera);
+
+        if let Some(camera_buffer) = &self.camera_buffer {
             self.queue
                 .write_buffer(camera_buffer, 0, bytemuck::cast_slice(&[camera_uniform]));
+        } else {
+            // first camera, create the buffer it lives in
+            let camera_buffer = self
+                .device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("Camera Buffer"),
+                    contents: bytemuck::cast_slice(&[camera_uniform]),
+                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                });
+
+            let camera_bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("camera_bind_group"),
+                layout: &self.camera_bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: camera_buffer.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: self.lights.buffer.as_entire_binding(),
+                    },
+                ],
+            });
+
+            self.camera_buffer = Some(camera_buffer);
+            self.camera_bind_group = Some(camera_bind_group);
         }
+
+        self.camera = Some(camera);
+        self.camera_uniform = Some(camera_uniform);
     }
 }
 
@@ -404,9 +1364,323 @@ impl Renderer {
 // this is used during renderer instantiation
 #[derive(Debug, Clone)]
 pub enum RendererBackends {
+    // the preferred backends of the platform, tried in order
+    Auto,
     OpenGL,
     Metal,
     Vulkan,
     Dx12,
     BrowserWebGL,
 }
+
+impl RendererBackends {
+    // The backends to try, in order of preference
+    pub(crate) fn candidates(&self) -> Vec<Backend> {
+        match self {
+            RendererBackends::Auto => {
+                if cfg!(target_arch = "wasm32") {
+                    vec![Backend::BrowserWebGpu, Backend::Gl]
+                } else if cfg!(any(target_os = "macos", target_os = "ios")) {
+                    vec![Backend::Metal]
+                } else if cfg!(target_os = "windows") {
+                    vec![Backend::Dx12, Backend::Vulkan, Backend::Gl]
+                } else {
+                    vec![Backend::Vulkan, Backend::Gl]
+                }
+            }
+            RendererBackends::OpenGL => vec![Backend::Gl],
+            RendererBackends::Metal => vec![Backend::Metal],
+            RendererBackends::Vulkan => vec![Backend::Vulkan],
+            RendererBackends::Dx12 => vec![Backend::Dx12],
+            RendererBackends::BrowserWebGL => vec![Backend::BrowserWebGpu],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::Vertex;
+
+    // needs a GPU or a software adapter such as llvmpipe
+    fn headless(width: u32, height: u32) -> Renderer {
+        pollster::block_on(Renderer::new_headless(
+            width,
+            height,
+            RendererBackends::Auto,
+        ))
+        .expect("No adapter for a headless renderer")
+    }
+
+    #[test]
+    #[ignore = "needs a GPU or software adapter"]
+    fn clear_color_is_read_back() {
+        let mut renderer = headless(64, 8);
+        renderer.render(1.0, 0.0, 0.0).unwrap();
+        let image = renderer.read_pixels().unwrap();
+
+        assert_eq!(image.dimensions(), (64, 8));
+        // render() clears alpha to 0.5, only the color is checked
+        assert!(image.pixels().all(|pixel| pixel.0[..3] == [255, 0, 0]));
+    }
+
+    #[test]
+    #[ignore = "needs a GPU or software adapter"]
+    fn read_pixels_strips_row_padding() {
+        // 37 pixels are 148 bytes, padded to 256 per row in the copy
+        let (width, height) = (37, 5);
+        let mut renderer = headless(width, height);
+        let white = RgbaImage::from_pixel(1, 1, image::Rgba([255; 4]));
+        let texture = renderer
+            .load_texture_from_image(
+                &DynamicImage::ImageRgba8(white),
+                "white",
+                TextureOptions::pixel_art(),
+            )
+            .unwrap();
+        // covers everything right of the centre
+        let quad = renderer.upload_mesh(
+            &[
+                Vertex::new([0.0, -100.0, 0.0], [0.0, 0.0]),
+                Vertex::new([100.0, -100.0, 0.0], [0.0, 0.0]),
+                Vertex::new([100.0, 100.0, 0.0], [0.0, 0.0]),
+                Vertex::new([0.0, 100.0, 0.0], [0.0, 0.0]),
+            ],
+            &[0u16, 1, 2, 0, 2, 3],
+        );
+        renderer.update_camera(&Camera::orthographic(
+            (0.0, 0.0, 1.0).into(),
+            (0.0, 0.0, 0.0).into(),
+            Vector3::unit_y(),
+            1.0,
+            2.0,
+            0.1,
+            10.0,
+        ));
+        renderer.draw(quad, texture, Matrix4::from_scale(1.0));
+        renderer.render(0.0, 0.0, 1.0).unwrap();
+        let image = renderer.read_pixels().unwrap();
+
+        assert_eq!(image.dimensions(), (width, height));
+        for y in 0..height {
+            for x in (0..width).filter(|&x| x != width / 2) {
+                let expected = if x < width / 2 {
+                    [0, 0, 255]
+                } else {
+                    [255, 255, 255]
+                };
+                assert_eq!(image.get_pixel(x, y).0[..3], expected, "pixel {x}, {y}");
+            }
+        }
+    }
+
+    #[test]
+    #[ignore = "needs a GPU or software adapter"]
+    fn text_is_gone_the_frame_after_it_was_drawn() {
+        let mut renderer = headless(64, 32);
+        let font = renderer
+            .load_font(include_bytes!("../../assets/fonts/DejaVuSans.ttf"))
+            .unwrap();
+
+        renderer.draw_text(font, "Hi", Vector2::new(4.0, 4.0), 24.0, [1.0; 4]);
+        renderer.render(0.0, 0.0, 1.0).unwrap();
+        let image = renderer.read_pixels().unwrap();
+        assert!(image.pixels().any(|pixel| pixel.0[..3] != [0, 0, 255]));
+
+        // nothing queued, the batches of the last frame must not be drawn again
+        renderer.render(0.0, 0.0, 1.0).unwrap();
+        assert_eq!(renderer.text.batch_count(), 0);
+        let image = renderer.read_pixels().unwrap();
+        assert!(image.pixels().all(|pixel| pixel.0[..3] == [0, 0, 255]));
+    }
+
+    #[test]
+    #[ignore = "needs a GPU or software adapter"]
+    fn measure_text_matches_the_drawn_layout() {
+        let mut renderer = headless(8, 8);
+        let font = renderer
+            .load_font(include_bytes!("../../assets/fonts/DejaVuSans.ttf"))
+            .unwrap();
+
+        let options = TextOptions::new(20.0, [1.0; 4]);
+        let one = renderer.measure_text(font, "Hello", &options);
+        let two = renderer.measure_text(font, "Hello\nHello", &options);
+        assert!(one.x > 0.0 && one.y > 0.0);
+        assert_eq!(two.x, one.x);
+        assert_eq!(two.y, 2.0 * one.y);
+
+        let wrapped = renderer.measure_text(font, "Hello Hello", &options.with_max_width(one.x));
+        assert_eq!(wrapped, two);
+
+        // unknown fonts measure nothing
+        let missing = renderer.measure_text(FontHandle(99), "Hello", &options);
+        assert_eq!(missing, Vector2::new(0.0, 0.0));
+    }
+
+    #[test]
+    #[ignore = "needs a GPU or software adapter"]
+    fn double_sided_material_draws_back_faces() {
+        let mut renderer = headless(8, 8);
+        let white = RgbaImage::from_pixel(1, 1, image::Rgba([255; 4]));
+        let texture = renderer
+            .load_texture_from_image(
+                &DynamicImage::ImageRgba8(white),
+                "white",
+                TextureOptions::pixel_art(),
+            )
+            .unwrap();
+        // wound clockwise, so the camera looks at its back
+        let back = renderer.upload_mesh(
+            &[
+                Vertex::new([-100.0, -100.0, 0.0], [0.0, 0.0]),
+                Vertex::new([0.0, 100.0, 0.0], [0.0, 0.0]),
+                Vertex::new([100.0, -100.0, 0.0], [0.0, 0.0]),
+            ],
+            &[0u16, 1, 2],
+        );
+        renderer.update_camera(&Camera::orthographic(
+            (0.0, 0.0, 1.0).into(),
+            (0.0, 0.0, 0.0).into(),
+            Vector3::unit_y(),
+            1.0,
+            2.0,
+            0.1,
+            10.0,
+        ));
+
+        let draw = |renderer: &mut Renderer, material| {
+            renderer.draw_with_material(material, back, texture, &[Instance::default()]);
+            renderer.render(0.0, 0.0, 0.0).unwrap();
+            renderer.read_pixels().unwrap().get_pixel(4, 4).0[..3].to_vec()
+        };
+        let culled = renderer.basic_material(BlendMode::Opaque);
+        assert_eq!(draw(&mut renderer, culled), [0, 0, 0]);
+        let double_sided = renderer.double_sided_material(BlendMode::Opaque);
+        assert_ne!(double_sided, culled);
+        assert_eq!(
+            renderer.double_sided_material(BlendMode::Opaque),
+            double_sided
+        );
+        assert_eq!(draw(&mut renderer, double_sided), [255, 255, 255]);
+    }
+
+    #[test]
+    #[ignore = "needs a GPU or software adapter"]
+    fn large_mesh_data_is_uploaded_with_32_bit_indices() {
+        let mut renderer = headless(8, 8);
+        let small = crate::assets::mesh_data::tests::strip(300).upload(&mut renderer);
+        let large = crate::assets::mesh_data::tests::strip(70_000).upload(&mut renderer);
+
+        let format = |handle: MeshHandle| {
+            let mesh = renderer.meshes.get(handle.0).unwrap();
+            mesh.index_buffer.as_ref().map(|(_, format)| *format)
+        };
+        assert_eq!(format(small), Some(wgpu::IndexFormat::Uint16));
+        assert_eq!(format(large), Some(wgpu::IndexFormat::Uint32));
+        assert_eq!(
+            renderer.meshes.get(large.0).unwrap().num_elements,
+            69_998 * 3
+        );
+    }
+
+    #[test]
+    #[ignore = "needs a GPU or software adapter"]
+    fn builder_depth_config_is_used_from_the_start() {
+        let renderer = pollster::block_on(
+            Renderer::builder()
+                .depth_config(DepthConfig::disabled())
+                .build_headless(8, 8),
+        )
+        .unwrap();
+        assert_eq!(renderer.depth_config(), DepthConfig::disabled());
+        assert!(renderer.depth_texture.is_none());
+    }
+
+    #[test]
+    fn hot_reload_paths_prefer_the_shader_directory() {
+        let basic = PathBuf::from("shaders/basic.wgsl");
+        let built_in = [(MaterialHandle(0), basic.clone())];
+        let own = Path::new("game/water.wgsl");
+
+        // a built-in material follows the directory, even with a path of its own
+        assert_eq!(
+            Renderer::hot_reload_path(MaterialHandle(0), None, &built_in),
+            Some(basic.clone())
+        );
+        assert_eq!(
+            Renderer::hot_reload_path(MaterialHandle(0), Some(own), &built_in),
+            Some(basic)
+        );
+        assert_eq!(
+            Renderer::hot_reload_path(MaterialHandle(1), Some(own), &built_in),
+            Some(own.to_path_buf())
+        );
+        // created from source, nothing to reload from
+        assert_eq!(
+            Renderer::hot_reload_path(MaterialHandle(2), None, &built_in),
+            None
+        );
+    }
+
+    #[test]
+    #[ignore = "needs a GPU or software adapter"]
+    fn changed_shader_file_is_reloaded() {
+        let dir = std::env::temp_dir().join(format!("bine-{}-reload", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let basic = include_str!("../../shaders/basic.wgsl");
+        let path = dir.join("water.wgsl");
+        std::fs::write(&path, basic).unwrap();
+
+        let mut renderer = headless(8, 8);
+        let material = renderer
+            .create_material(MaterialDescriptor::from_file("water", &path).unwrap())
+            .unwrap();
+        renderer.enable_shader_hot_reload(&dir);
+
+        let changed = format!("// changed\n{basic}");
+        std::fs::write(&path, &changed).unwrap();
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(5))
+            .unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+        renderer.reload_changed_shaders();
+
+        let reloaded = renderer.materials.get(material.0).unwrap();
+        assert_eq!(reloaded.source(), changed);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    #[ignore = "needs a GPU or software adapter"]
+    fn runtime_settings_survive_renderer_recreation() {
+        let mut old = headless(8, 8);
+        old.set_depth_config(DepthConfig::disabled());
+        old.set_auto_aspect(false);
+        old.set_ambient_light([0.3, 0.2, 0.1]);
+        let light = old
+            .add_light(crate::renderer::PointLight::new(
+                Point3::new(1.0, 2.0, 3.0),
+                [1.0; 3],
+                2.0,
+                10.0,
+            ))
+            .unwrap();
+        let sample_count = old.supported_sample_counts().into_iter().max().unwrap();
+        old.set_sample_count(sample_count).unwrap();
+
+        let settings = old.into_runtime_settings();
+        let mut renderer = headless(8, 8);
+        renderer.apply_runtime_settings(settings);
+
+        assert_eq!(renderer.depth_config(), DepthConfig::disabled());
+        assert!(!renderer.auto_aspect);
+        assert_eq!(renderer.sample_count(), sample_count);
+        assert_eq!(renderer.lights.ambient, [0.3, 0.2, 0.1]);
+        assert_eq!(
+            renderer.light_mut(light).map(|light| light.position()),
+            Some(Point3::new(1.0, 2.0, 3.0))
+        );
+        renderer.render(0.0, 0.0, 0.0).unwrap();
+    }
+}
@@ -0,0 +1,125 @@
+//! Bine renderer
+//!
+//! Author: BEKs => 18.10.2026
+//!
+//! 2D sprites collected by the game into a SpriteBatch and handed to
+//! Renderer::draw_sprite_batch. Sprites live in the xy plane and are
+//! meant to be viewed through a Camera2D.
+
+use cgmath::{Rad, Vector2};
+
+use super::TextureHandle;
+
+// === Rect struct
+// Area of a texture in uv coordinates, (0, 0) being the top left
+// corner and (1, 1) the bottom right one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Rect {
+    // The whole texture
+    pub const FULL: Rect = Rect::new(0.0, 0.0, 1.0, 1.0);
+
+    pub const fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    // Splits the rect into a grid of equally sized cells, row by row
+    // starting at the top left. Handy for uniform sprite sheets.
+    pub fn split_grid(&self, columns: u32, rows: u32) -> Vec<Rect> {
+        let width = self.width / columns as f32;
+        let height = self.height / rows as f32;
+        (0..rows)
+            .flat_map(|row| {
+                (0..columns).map(move |column| {
+                    Rect::new(
+                        self.x + column as f32 * width,
+                        self.y + row as f32 * height,
+                        width,
+                        height,
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+impl Default for Rect {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+// === Sprite struct
+// A single textured quad centred on its position
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Sprite {
+    pub(crate) texture: TextureHandle,
+    pub(crate) position: Vector2<f32>,
+    pub(crate) size: Vector2<f32>,
+    pub(crate) rotation: Rad<f32>,
+    pub(crate) uv_rect: Rect,
+    pub(crate) tint: [f32; 4],
+    pub(crate) layer: i32,
+}
+
+// === SpriteBatch struct
+// Sprites are drawn in order of their layer. Within a layer they are
+// grouped by texture to save draw calls, so overlapping sprites that
+// must be drawn in a fixed order belong on different layers.
+#[derive(Debug, Default)]
+pub struct SpriteBatch {
+    pub(crate) sprites: Vec<Sprite>,
+}
+
+impl SpriteBatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Queues a sprite. Position and size are in world units, the
+    // rotation turns the sprite around its centre.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_sprite(
+        &mut self,
+        texture: TextureHandle,
+        position: Vector2<f32>,
+        size: Vector2<f32>,
+        rotation: Rad<f32>,
+        uv_rect: Rect,
+        tint: [f32; 4],
+        layer: i32,
+    ) {
+        self.sprites.push(Sprite {
+            texture,
+            position,
+            size,
+            rotation,
+            uv_rect,
+            tint,
+            layer,
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.sprites.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sprites.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.sprites.clear();
+    }
+}
@@ -0,0 +1,406 @@
+//! Bine renderer
+//!
+//! Author: BEKs => 18.10.2026
+//!
+//! GPU side of the sprite batch. Every frame the queued sprites are
+//! sorted, expanded into quads in one dynamic vertex buffer and drawn
+//! with one draw call per run of sprites sharing a texture.
+
+use std::ops::Range;
+
+use bytemuck::{Pod, Zeroable};
+use cgmath::{Angle, Vector2};
+use wgpu::util::DeviceExt;
+
+use super::{
+    BlendMode, TextureHandle,
+    material::{PipelineSettings, SharedLayouts, TEXTURE_BIND_GROUP},
+    sprite::Sprite,
+    texture::TextureRegistry,
+};
+
+// === SpriteVertex struct
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct SpriteVertex {
+    position: [f32; 2],
+    tex_coords: [f32; 2],
+    tint: [f32; 4],
+}
+
+impl SpriteVertex {
+    const ATTRIB: [wgpu::VertexAttribute; 3] =
+        wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x2, 2 => Float32x4];
+
+    fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<SpriteVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIB,
+        }
+    }
+}
+
+// === SpriteRenderer struct
+pub(crate) struct SpriteRenderer {
+    shader: wgpu::ShaderModule,
+    pipeline_layout: wgpu::PipelineLayout,
+    pipeline: wgpu::RenderPipeline,
+
+    vertex_buffer: wgpu::Buffer,
+    index_buffer: wgpu::Buffer,
+    capacity: usize,
+
+    // sprites queued for the current frame
+    sprites: Vec<Sprite>,
+    vertices: Vec<SpriteVertex>,
+    batches: Vec<(TextureHandle, Range<u32>)>,
+}
+
+impl SpriteRenderer {
+    const INITIAL_CAPACITY: usize = 256;
+
+    pub(crate) fn new(
+        device: &wgpu::Device,
+        layouts: &SharedLayouts,
+        settings: &PipelineSettings,
+    ) -> Self {
+        let shader = device.create_shader_module(wgpu::include_wgsl!("../../shaders/sprite.wgsl"));
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Sprite Pipeline Layout"),
+            bind_group_layouts: &[layouts.texture, layouts.camera],
+            push_constant_ranges: &[],
+        });
+        let pipeline = Self::create_pipeline(device, &pipeline_layout, &shader, settings);
+
+        Self {
+            shader,
+            pipeline_layout,
+            pipeline,
+            vertex_buffer: Self::create_vertex_buffer(device, Self::INITIAL_CAPACITY),
+            index_buffer: Self::create_index_buffer(device, Self::INITIAL_CAPACITY),
+            capacity: Self::INITIAL_CAPACITY,
+            sprites: Vec::new(),
+            vertices: Vec::new(),
+            batches: Vec::new(),
+        }
+    }
+
+    fn create_pipeline(
+        device: &wgpu::Device,
+        pipeline_layout: &wgpu::PipelineLayout,
+        shader: &wgpu::ShaderModule,
+        settings: &PipelineSettings,
+    ) -> wgpu::RenderPipeline {
+        // sprites are ordered by layer, the depth buffer is
+        // neither tested nor written
+        let depth_stencil =
+            settings
+                .depth
+                .depth_stencil_state(false)
+                .map(|state| wgpu::DepthStencilState {
+                    depth_compare: wgpu::CompareFunction::Always,
+                    ..state
+                });
+
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Sprite Pipeline"),
+            layout: Some(pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: Some("vs_main"),
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                buffers: &[SpriteVertex::desc()],
+            },
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                // mirrored sprites (negative size) are still visible
+                cull_mode: None,
+                ..Default::default()
+            },
+            depth_stencil,
+            multisample: wgpu::MultisampleState {
+                count: settings.sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point: Some("fs_main"),
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: settings.format,
+                    blend: Some(BlendMode::Alpha.blend_state()),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            multiview: None,
+            cache: None,
+        })
+    }
+
+    // Rebuilds the pipeline after the render target state changed
+    pub(crate) fn rebuild_pipeline(&mut self, device: &wgpu::Device, settings: &PipelineSettings) {
+        self.pipeline =
+            Self::create_pipeline(device, &self.pipeline_layout, &self.shader, settings);
+    }
+
+    fn create_vertex_buffer(device: &wgpu::Device, capacity: usize) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Sprite Vertex Buffer"),
+            size: (capacity * 4 * std::mem::size_of::<SpriteVertex>()) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    // The indices never change, two triangles for every quad
+    fn create_index_buffer(device: &wgpu::Device, capacity: usize) -> wgpu::Buffer {
+        let indices: Vec<u32> = (0..capacity as u32)
+            .flat_map(|quad| {
+                let first = quad * 4;
+                [first, first + 1, first + 2, first, first + 2, first + 3]
+            })
+            .collect();
+        device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Sprite Index Buffer"),
+            contents: bytemuck::cast_slice(&indices),
+            usage: wgpu::BufferUsages::INDEX,
+        })
+    }
+
+    pub(crate) fn push(&mut self, sprites: impl IntoIterator<Item = Sprite>) {
+        self.sprites.extend(sprites);
+    }
+
+    // Sorts the queued sprites and uploads their quads. Sprites with
+    // unloaded textures are dropped.
+    pub(crate) fn prepare(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        textures: &TextureRegistry,
+    ) {
+        batch_sprites(
+            &mut self.sprites,
+            |texture| textures.get(texture).is_some(),
+            &mut self.vertices,
+            &mut self.batches,
+        );
+        if self.sprites.is_empty() {
+            return;
+        }
+
+        if self.sprites.len() > self.capacity {
+            self.capacity = self.sprites.len().next_power_of_two();
+            self.vertex_buffer = Self::create_vertex_buffer(device, self.capacity);
+            self.index_buffer = Self::create_index_buffer(device, self.capacity);
+        }
+        queue.write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(&self.vertices));
+    }
+
+    // Expects the camera to be bound already
+    pub(crate) fn draw(&self, render_pass: &mut wgpu::RenderPass, textures: &TextureRegistry) {
+        if self.batches.is_empty() {
+            return;
+        }
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+        for (texture, indices) in &self.batches {
+            if let Some(texture) = textures.get(*texture) {
+                render_pass.set_bind_group(TEXTURE_BIND_GROUP, &texture.bind_group, &[]);
+                render_pass.draw_indexed(indices.clone(), 0, 0..1);
+            }
+        }
+    }
+
+    // Also forgets the prepared quads, so nothing is drawn again
+    // when the next frame skips prepare()
+    pub(crate) fn clear(&mut self) {
+        self.sprites.clear();
+        self.vertices.clear();
+        self.batches.clear();
+    }
+
+    #[cfg(test)]
+    pub(crate) fn batch_count(&self) -> usize {
+        self.batches.len()
+    }
+}
+
+// Drops sprites with unloaded textures, sorts the rest by layer and
+// texture and expands them into quads. Every run of sprites sharing a
+// texture becomes one range of indices to draw.
+fn batch_sprites(
+    sprites: &mut Vec<Sprite>,
+    is_loaded: impl Fn(TextureHandle) -> bool,
+    vertices: &mut Vec<SpriteVertex>,
+    batches: &mut Vec<(TextureHandle, Range<u32>)>,
+) {
+    vertices.clear();
+    batches.clear();
+    sprites.retain(|sprite| is_loaded(sprite.texture));
+    // stable, sprites of a layer and texture keep their order
+    sprites.sort_by_key(|sprite| (sprite.layer, sprite.texture.0));
+    for sprite in sprites.iter() {
+        let first = vertices.len() as u32 / 4 * 6;
+        vertices.extend(quad(sprite));
+        match batches.last_mut() {
+            Some((texture, indices)) if *texture == sprite.texture => indices.end += 6,
+            _ => batches.push((sprite.texture, first..first + 6)),
+        }
+    }
+}
+
+// Corners in counter clockwise order starting at the bottom left
+fn quad(sprite: &Sprite) -> [SpriteVertex; 4] {
+    let (sin, cos) = sprite.rotation.sin_cos();
+    let half = sprite.size / 2.0;
+    let uv = sprite.uv_rect;
+    let corner = |x: f32, y: f32, u: f32, v: f32| {
+        let offset = Vector2::new(x * half.x, y * half.y);
+        let rotated = Vector2::new(
+            offset.x * cos - offset.y * sin,
+            offset.x * sin + offset.y * cos,
+        );
+        let position = sprite.position + rotated;
+        SpriteVertex {
+            position: position.into(),
+            tex_coords: [u, v],
+            tint: sprite.tint,
+        }
+    };
+    [
+        corner(-1.0, -1.0, uv.x, uv.y + uv.height),
+        corner(1.0, -1.0, uv.x + uv.width, uv.y + uv.height),
+        corner(1.0, 1.0, uv.x + uv.width, uv.y),
+        corner(-1.0, 1.0, uv.x, uv.y),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{Deg, Rad};
+
+    use super::*;
+    use crate::renderer::Rect;
+
+    fn sprite(texture: u32, layer: i32, x: f32) -> Sprite {
+        Sprite {
+            texture: TextureHandle(texture),
+            position: Vector2::new(x, 0.0),
+            size: Vector2::new(1.0, 1.0),
+            rotation: Rad(0.0),
+            uv_rect: Rect::FULL,
+            tint: [1.0; 4],
+            layer,
+        }
+    }
+
+    fn batch(
+        mut sprites: Vec<Sprite>,
+        loaded: &[u32],
+    ) -> (
+        Vec<Sprite>,
+        Vec<SpriteVertex>,
+        Vec<(TextureHandle, Range<u32>)>,
+    ) {
+        let (mut vertices, mut batches) = (Vec::new(), Vec::new());
+        batch_sprites(
+            &mut sprites,
+            |texture| loaded.contains(&texture.0),
+            &mut vertices,
+            &mut batches,
+        );
+        (sprites, vertices, batches)
+    }
+
+    fn assert_close(a: [f32; 2], b: [f32; 2]) {
+        assert!(
+            (a[0] - b[0]).abs() < 1e-5 && (a[1] - b[1]).abs() < 1e-5,
+            "{a:?} != {b:?}"
+        );
+    }
+
+    #[test]
+    fn sprites_are_sorted_by_layer() {
+        let (sprites, vertices, batches) = batch(
+            vec![sprite(0, 2, 0.0), sprite(0, -1, 1.0), sprite(0, 0, 2.0)],
+            &[0],
+        );
+        let layers: Vec<_> = sprites.iter().map(|sprite| sprite.layer).collect();
+        assert_eq!(layers, [-1, 0, 2]);
+        // quads follow the sorted order
+        assert_eq!(vertices.len(), 12);
+        assert_close(vertices[0].position, [0.5, -0.5]);
+        // same texture on every layer, one draw
+        assert_eq!(batches, [(TextureHandle(0), 0..18)]);
+    }
+
+    #[test]
+    fn runs_sharing_a_texture_are_merged() {
+        let (_, _, batches) = batch(
+            vec![
+                sprite(1, 0, 0.0),
+                sprite(2, 0, 1.0),
+                sprite(1, 0, 2.0),
+                sprite(2, 1, 3.0),
+                sprite(1, 1, 4.0),
+            ],
+            &[1, 2],
+        );
+        assert_eq!(
+            batches,
+            [
+                (TextureHandle(1), 0..12),
+                (TextureHandle(2), 12..18),
+                (TextureHandle(1), 18..24),
+                (TextureHandle(2), 24..30),
+            ]
+        );
+    }
+
+    #[test]
+    fn unloaded_textures_are_dropped() {
+        let (sprites, vertices, batches) = batch(vec![sprite(0, 0, 0.0), sprite(7, 0, 1.0)], &[0]);
+        assert_eq!(sprites.len(), 1);
+        assert_eq!(vertices.len(), 4);
+        assert_eq!(batches, [(TextureHandle(0), 0..6)]);
+
+        let (sprites, vertices, batches) = batch(vec![sprite(7, 0, 0.0)], &[]);
+        assert!(sprites.is_empty() && vertices.is_empty() && batches.is_empty());
+    }
+
+    #[test]
+    fn quad_corners_are_rotated_around_the_centre() {
+        let sprite = Sprite {
+            position: Vector2::new(10.0, 20.0),
+            size: Vector2::new(4.0, 2.0),
+            rotation: Deg(90.0).into(),
+            uv_rect: Rect::new(0.25, 0.5, 0.25, 0.5),
+            ..sprite(0, 0, 0.0)
+        };
+        let corners = quad(&sprite);
+        // a quarter turn counter clockwise moves the bottom left corner
+        // (-2, -1) from the centre to (1, -2)
+        assert_close(corners[0].position, [11.0, 18.0]);
+        assert_close(corners[1].position, [11.0, 22.0]);
+        assert_close(corners[2].position, [9.0, 22.0]);
+        assert_close(corners[3].position, [9.0, 18.0]);
+    }
+
+    #[test]
+    fn quad_uvs_have_v_pointing_down() {
+        let sprite = Sprite {
+            uv_rect: Rect::new(0.25, 0.5, 0.25, 0.5),
+            ..sprite(0, 0, 0.0)
+        };
+        let uvs: Vec<_> = quad(&sprite)
+            .iter()
+            .map(|vertex| vertex.tex_coords)
+            .collect();
+        // bottom left, bottom right, top right, top left
+        assert_eq!(uvs, [[0.25, 1.0], [0.5, 1.0], [0.5, 0.5], [0.25, 0.5]]);
+    }
+}
@@ -0,0 +1,268 @@
+//! Bine renderer
+//!
+//! Author: BEKs => 18.10.2026
+//!
+//! Render targets the renderer can draw into. This is either the
+//! swapchain of a window surface or an offscreen texture used when
+//! rendering headless (CI, automated tests, screenshots).
+
+use wgpu::{PresentMode, SurfaceTexture, TextureFormat, TextureView, TextureViewDescriptor};
+
+// === RenderTarget enum
+pub(crate) enum RenderTarget {
+    Surface {
+        surface: wgpu::Surface<'static>,
+        config: wgpu::SurfaceConfiguration,
+        // present modes the adapter supports for this surface
+        present_modes: Vec<PresentMode>,
+    },
+    Offscreen {
+        texture: wgpu::Texture,
+    },
+}
+
+impl RenderTarget {
+    pub(crate) const OFFSCREEN_FORMAT: TextureFormat = TextureFormat::Rgba8UnormSrgb;
+
+    pub(crate) fn create_offscreen(device: &wgpu::Device, width: u32, height: u32) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Offscreen Target"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: Self::OFFSCREEN_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        Self::Offscreen { texture }
+    }
+
+    pub(crate) fn format(&self) -> TextureFormat {
+        match self {
+            Self::Surface { config, .. } => config.format,
+            Self::Offscreen { texture } => texture.format(),
+        }
+    }
+
+    // Reconfigure the surface or recreate the offscreen texture
+    // with the new dimensions
+    pub(crate) fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        match self {
+            Self::Surface {
+                surface, config, ..
+            } => {
+                config.width = width;
+                config.height = height;
+                surface.configure(device, config);
+            }
+            Self::Offscreen { .. } => *self = Self::create_offscreen(device, width, height),
+        }
+    }
+
+    // Multisampled color attachment that is resolved into the frame
+    pub(crate) fn create_msaa_view(
+        &self,
+        device: &wgpu::Device,
+        (width, height): (u32, u32),
+        sample_count: u32,
+    ) -> TextureView {
+        device
+            .create_texture(&wgpu::TextureDescriptor {
+                label: Some("MSAA Color Target"),
+                size: wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count,
+                dimension: wgpu::TextureDimension::D2,
+                format: self.format(),
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            })
+            .create_view(&TextureViewDescriptor::default())
+    }
+
+    // Offscreen targets are never presented and have no present mode
+    pub(crate) fn present_mode(&self) -> Option<PresentMode> {
+        match self {
+            Self::Surface { config, .. } => Some(config.present_mode),
+            Self::Offscreen { .. } => None,
+        }
+    }
+
+    // Reconfigures the surface with the closest supported present mode
+    // and returns the one that was picked
+    pub(crate) fn set_present_mode(
+        &mut self,
+        device: &wgpu::Device,
+        mode: PresentMode,
+    ) -> Option<PresentMode> {
+        match self {
+            Self::Surface {
+                surface,
+                config,
+                present_modes,
+            } => {
+                config.present_mode = choose_present_mode(mode, present_modes);
+                surface.configure(device, config);
+                Some(config.present_mode)
+            }
+            Self::Offscreen { .. } => None,
+        }
+    }
+
+    // Acquire the texture the next frame will be drawn into
+    pub(crate) fn acquire(&self) -> Result<Frame, wgpu::SurfaceError> {
+        match self {
+            Self::Surface { surface, .. } => {
+                let surface_texture = surface.get_current_texture()?;
+                let view = surface_texture
+                    .texture
+                    .create_view(&TextureViewDescriptor::default());
+                Ok(Frame {
+                    view,
+                    surface_texture: Some(surface_texture),
+                })
+            }
+            Self::Offscreen { texture } => Ok(Frame {
+                view: texture.create_view(&TextureViewDescriptor::default()),
+                surface_texture: None,
+            }),
+        }
+    }
+}
+
+// === Frame struct
+// A single frame acquired from a render target.
+pub(crate) struct Frame {
+    pub(crate) view: TextureView,
+    surface_texture: Option<SurfaceTexture>,
+}
+
+impl Frame {
+    // Present the frame to the window. Offscreen frames stay
+    // in their texture until they are read back.
+    pub(crate) fn present(self) {
+        if let Some(surface_texture) = self.surface_texture {
+            surface_texture.present();
+        }
+    }
+}
+
+// Resolves the requested present mode against the supported ones.
+// The Auto modes are resolved the same way wgpu does it so the caller
+// learns the concrete mode, Fifo is supported everywhere and is the
+// last resort.
+pub(crate) fn choose_present_mode(
+    requested: PresentMode,
+    supported: &[PresentMode],
+) -> PresentMode {
+    let preferred: &[PresentMode] = match requested {
+        PresentMode::AutoVsync => &[PresentMode::FifoRelaxed],
+        PresentMode::AutoNoVsync => &[PresentMode::Immediate, PresentMode::Mailbox],
+        PresentMode::Mailbox => &[PresentMode::Mailbox, PresentMode::Immediate],
+        PresentMode::Immediate => &[PresentMode::Immediate, PresentMode::Mailbox],
+        PresentMode::FifoRelaxed => &[PresentMode::FifoRelaxed],
+        PresentMode::Fifo => &[],
+    };
+    preferred
+        .iter()
+        .copied()
+        .find(|mode| supported.contains(mode))
+        .unwrap_or(PresentMode::Fifo)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unsupported_modes_fall_back_to_fifo() {
+        let supported = [PresentMode::Fifo];
+        for requested in [
+            PresentMode::AutoVsync,
+            PresentMode::AutoNoVsync,
+            PresentMode::Mailbox,
+            PresentMode::Immediate,
+            PresentMode::FifoRelaxed,
+            PresentMode::Fifo,
+        ] {
+            assert_eq!(
+                choose_present_mode(requested, &supported),
+                PresentMode::Fifo,
+                "{requested:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn empty_support_list_falls_back_to_fifo() {
+        assert_eq!(
+            choose_present_mode(PresentMode::Mailbox, &[]),
+            PresentMode::Fifo
+        );
+    }
+
+    #[test]
+    fn supported_modes_are_kept() {
+        let supported = [
+            PresentMode::Fifo,
+            PresentMode::FifoRelaxed,
+            PresentMode::Mailbox,
+            PresentMode::Immediate,
+        ];
+        for mode in supported {
+            assert_eq!(choose_present_mode(mode, &supported), mode);
+        }
+    }
+
+    #[test]
+    fn low_latency_modes_substitute_each_other() {
+        assert_eq!(
+            choose_present_mode(
+                PresentMode::Mailbox,
+                &[PresentMode::Fifo, PresentMode::Immediate]
+            ),
+            PresentMode::Immediate
+        );
+        assert_eq!(
+            choose_present_mode(
+                PresentMode::Immediate,
+                &[PresentMode::Fifo, PresentMode::Mailbox]
+            ),
+            PresentMode::Mailbox
+        );
+    }
+
+    #[test]
+    fn auto_modes_resolve_like_wgpu() {
+        let supported = [
+            PresentMode::Fifo,
+            PresentMode::FifoRelaxed,
+            PresentMode::Mailbox,
+            PresentMode::Immediate,
+        ];
+        assert_eq!(
+            choose_present_mode(PresentMode::AutoVsync, &supported),
+            PresentMode::FifoRelaxed
+        );
+        assert_eq!(
+            choose_present_mode(PresentMode::AutoNoVsync, &supported),
+            PresentMode::Immediate
+        );
+        assert_eq!(
+            choose_present_mode(
+                PresentMode::AutoNoVsync,
+                &[PresentMode::Fifo, PresentMode::Mailbox]
+            ),
+            PresentMode::Mailbox
+        );
+    }
+}
@@ -0,0 +1,330 @@
+//! Bine renderer
+//!
+//! Author: BEKs => 18.10.2026
+//!
+//! Text drawn with TrueType and OpenType fonts. Text is laid out in
+//! screen pixels with (0, 0) at the top left corner of the render
+//! target, independent of the active camera, which suits scores,
+//! counters and menus.
+
+use cgmath::Vector2;
+
+// === FontHandle struct
+// Returned from Renderer::load_font and used to refer to
+// the font when drawing text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FontHandle(pub(crate) u32);
+
+// === TextAlign enum
+// Without a max width the text is aligned to its position: it starts,
+// is centred or ends there. With one, every line is aligned inside
+// the box that starts at the position and is max width wide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+// === TextOptions struct
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextOptions {
+    // height of the font in pixels
+    pub size: f32,
+    pub color: [f32; 4],
+    pub align: TextAlign,
+    // lines longer than this are wrapped between words
+    pub max_width: Option<f32>,
+    // multiplier for the distance between lines
+    pub line_spacing: f32,
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        Self {
+            size: 16.0,
+            color: [1.0, 1.0, 1.0, 1.0],
+            align: TextAlign::Left,
+            max_width: None,
+            line_spacing: 1.0,
+        }
+    }
+}
+
+impl TextOptions {
+    pub fn new(size: f32, color: [f32; 4]) -> Self {
+        Self {
+            size,
+            color,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    pub fn with_max_width(mut self, max_width: f32) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    pub fn with_line_spacing(mut self, line_spacing: f32) -> Self {
+        self.line_spacing = line_spacing;
+        self
+    }
+}
+
+// === PositionedGlyph struct
+// A glyph bitmap placed on screen, top left corner in whole pixels
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PositionedGlyph {
+    pub(crate) index: u16,
+    pub(crate) x: f32,
+    pub(crate) y: f32,
+    pub(crate) width: f32,
+    pub(crate) height: f32,
+}
+
+// === TextLayout struct
+pub(crate) struct TextLayout {
+    pub(crate) glyphs: Vec<PositionedGlyph>,
+    // width of the widest line and height of all lines
+    pub(crate) size: Vector2<f32>,
+}
+
+impl TextLayout {
+    // Breaks the text into lines and places the glyphs of every line,
+    // applying the kerning of the font between neighbouring glyphs
+    pub(crate) fn new(
+        font: &fontdue::Font,
+        text: &str,
+        position: Vector2<f32>,
+        options: &TextOptions,
+    ) -> Self {
+        let px = options.size;
+        let (ascent, line_height) = font
+            .horizontal_line_metrics(px)
+            .map(|metrics| (metrics.ascent, metrics.new_line_size))
+            .unwrap_or((px, px));
+        let line_height = (line_height * options.line_spacing).round();
+
+        let lines = Self::wrap(font, text, options);
+        let box_width = options.max_width.unwrap_or(0.0);
+        let mut glyphs = Vec::with_capacity(text.len());
+        let mut width: f32 = 0.0;
+        for (i, line) in lines.iter().enumerate() {
+            let line_width = Self::line_width(font, line, px);
+            width = width.max(line_width);
+            let x = match options.align {
+                TextAlign::Left => position.x,
+                TextAlign::Center => position.x + (box_width - line_width) / 2.0,
+                TextAlign::Right => position.x + box_width - line_width,
+            };
+            let baseline = (position.y + ascent).round() + i as f32 * line_height;
+
+            let mut pen = x.floor();
+            let mut previous = None;
+            for c in line.chars() {
+                let index = font.lookup_glyph_index(c);
+                if let Some(previous) = previous {
+                    pen += font
+                        .horizontal_kern_indexed(previous, index, px)
+                        .unwrap_or(0.0);
+                }
+                let metrics = font.metrics_indexed(index, px);
+                if metrics.width > 0 && metrics.height > 0 {
+                    glyphs.push(PositionedGlyph {
+                        index,
+                        x: (pen + metrics.xmin as f32).round(),
+                        y: baseline - (metrics.ymin + metrics.height as i32) as f32,
+                        width: metrics.width as f32,
+                        height: metrics.height as f32,
+                    });
+                }
+                pen += metrics.advance_width;
+                previous = Some(index);
+            }
+        }
+
+        Self {
+            glyphs,
+            size: Vector2::new(width, lines.len() as f32 * line_height),
+        }
+    }
+
+    // Splits at line breaks, then greedily fills every line with as
+    // many words as fit into the max width. Words wider than the max
+    // width get a line of their own.
+    fn wrap(font: &fontdue::Font, text: &str, options: &TextOptions) -> Vec<String> {
+        let Some(max_width) = options.max_width else {
+            return text.lines().map(String::from).collect();
+        };
+        let mut lines = Vec::new();
+        for paragraph in text.lines() {
+            let mut line = String::new();
+            for word in paragraph.split(' ') {
+                if line.is_empty() {
+                    line.push_str(word);
+                    continue;
+                }
+                let candidate = format!("{line} {word}");
+                if Self::line_width(font, &candidate, options.size) <= max_width {
+                    line = candidate;
+                } else {
+                    lines.push(std::mem::replace(&mut line, word.into()));
+                }
+            }
+            lines.push(line);
+        }
+        lines
+    }
+
+    fn line_width(font: &fontdue::Font, line: &str, px: f32) -> f32 {
+        let mut width = 0.0;
+        let mut previous = None;
+        for c in line.chars() {
+            let index = font.lookup_glyph_index(c);
+            if let Some(previous) = previous {
+                width += font
+                    .horizontal_kern_indexed(previous, index, px)
+                    .unwrap_or(0.0);
+            }
+            width += font.metrics_indexed(index, px).advance_width;
+            previous = Some(index);
+        }
+        width
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: f32 = 20.0;
+
+    fn font() -> fontdue::Font {
+        fontdue::Font::from_bytes(
+            include_bytes!("../../assets/fonts/DejaVuSans.ttf") as &[u8],
+            fontdue::FontSettings::default(),
+        )
+        .unwrap()
+    }
+
+    fn options() -> TextOptions {
+        TextOptions::new(SIZE, [1.0; 4])
+    }
+
+    fn width(font: &fontdue::Font, line: &str) -> f32 {
+        TextLayout::line_width(font, line, SIZE)
+    }
+
+    // left edge of the first glyph, the pen position plus its bearing
+    fn first_x(
+        font: &fontdue::Font,
+        line: &str,
+        position: Vector2<f32>,
+        options: &TextOptions,
+    ) -> f32 {
+        TextLayout::new(font, line, position, options).glyphs[0].x
+    }
+
+    #[test]
+    fn lines_are_wrapped_at_the_max_width() {
+        let font = font();
+        let max_width = width(&font, "one two");
+        let wrapping = options().with_max_width(max_width);
+
+        let lines = TextLayout::wrap(&font, "one two three four", &wrapping);
+        assert_eq!(lines, ["one two", "three", "four"]);
+        assert!(lines.iter().all(|line| width(&font, line) <= max_width));
+
+        // explicit line breaks are kept, with or without a max width
+        let lines = TextLayout::wrap(&font, "one\ntwo", &options());
+        assert_eq!(lines, ["one", "two"]);
+    }
+
+    #[test]
+    fn word_wider_than_the_box_gets_its_own_line() {
+        let font = font();
+        let options = options().with_max_width(width(&font, "tiny"));
+
+        let lines = TextLayout::wrap(&font, "a incomprehensibilities b", &options);
+        assert_eq!(lines, ["a", "incomprehensibilities", "b"]);
+    }
+
+    #[test]
+    fn kerning_is_applied_between_glyphs() {
+        let font = font();
+        assert!(width(&font, "AV") < width(&font, "A") + width(&font, "V"));
+
+        // the second glyph is placed by the same kerned advance
+        let layout = TextLayout::new(&font, "AV", Vector2::new(0.0, 0.0), &options());
+        let unkerned =
+            TextLayout::new(&font, "V", Vector2::new(width(&font, "A"), 0.0), &options());
+        assert!(layout.glyphs[1].x < unkerned.glyphs[0].x);
+    }
+
+    #[test]
+    fn lines_are_aligned_inside_the_max_width() {
+        let font = font();
+        let position = Vector2::new(10.0, 0.0);
+        let box_width = 200.0;
+        let line_width = width(&font, "Hi");
+        let left = first_x(&font, "Hi", position, &options());
+
+        let center = options()
+            .with_align(TextAlign::Center)
+            .with_max_width(box_width);
+        let offset = ((box_width - line_width) / 2.0 + position.x).floor() - position.x;
+        assert_eq!(first_x(&font, "Hi", position, &center), left + offset);
+
+        let right = options()
+            .with_align(TextAlign::Right)
+            .with_max_width(box_width);
+        let offset = (box_width - line_width + position.x).floor() - position.x;
+        assert_eq!(first_x(&font, "Hi", position, &right), left + offset);
+    }
+
+    #[test]
+    fn without_max_width_lines_are_aligned_around_the_position() {
+        let font = font();
+        let position = Vector2::new(100.0, 0.0);
+        let line_width = width(&font, "Hi");
+        let left = first_x(&font, "Hi", position, &options());
+
+        let center = options().with_align(TextAlign::Center);
+        let offset = (position.x - line_width / 2.0).floor() - position.x;
+        assert_eq!(first_x(&font, "Hi", position, &center), left + offset);
+
+        // right aligned text ends at the position
+        let right = options().with_align(TextAlign::Right);
+        let offset = (position.x - line_width).floor() - position.x;
+        assert_eq!(first_x(&font, "Hi", position, &right), left + offset);
+    }
+
+    #[test]
+    fn size_covers_the_widest_line_and_all_lines() {
+        let font = font();
+        let line_height = font
+            .horizontal_line_metrics(SIZE)
+            .unwrap()
+            .new_line_size
+            .round();
+
+        let layout = TextLayout::new(
+            &font,
+            "short\nmuch longer",
+            Vector2::new(0.0, 0.0),
+            &options(),
+        );
+        assert_eq!(layout.size.x, width(&font, "much longer"));
+        assert_eq!(layout.size.y, 2.0 * line_height);
+
+        let spaced = options().with_line_spacing(1.5);
+        let layout = TextLayout::new(&font, "a\nb", Vector2::new(0.0, 0.0), &spaced);
+        assert_eq!(layout.size.y, 2.0 * (line_height * 1.5).round());
+    }
+}
@@ -0,0 +1,335 @@
+//! Bine renderer
+//!
+//! Author: BEKs => 18.10.2026
+//!
+//! GPU side of text drawing. Glyphs are rasterized on first use into a
+//! glyph atlas that grows as needed, and every glyph is drawn as a
+//! sprite with a camera that maps one unit to one screen pixel.
+
+use std::collections::HashMap;
+
+use cgmath::{Rad, Vector2, Vector3};
+use image::{DynamicImage, GenericImage, Rgba, RgbaImage};
+use wgpu::util::DeviceExt;
+
+use super::{
+    Camera, CameraUniform, FontHandle, Rect, Texture, TextureHandle, TextureOptions,
+    material::{CAMERA_BIND_GROUP, PipelineSettings, SharedLayouts},
+    sprite::Sprite,
+    sprite_renderer::SpriteRenderer,
+    text::TextLayout,
+    texture::{LoadedTexture, TextureRegistry},
+};
+
+// === GlyphKey struct
+// A glyph of a font rasterized at one pixel size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct GlyphKey {
+    font: FontHandle,
+    index: u16,
+    size_bits: u32,
+}
+
+// === GlyphAtlas struct
+// CPU copy of the atlas, glyphs are packed into shelves of rows
+struct GlyphAtlas {
+    image: RgbaImage,
+    max_size: u32,
+    // pixel area of every rasterized glyph
+    glyphs: HashMap<GlyphKey, (u32, u32, u32, u32)>,
+    cursor: (u32, u32),
+    shelf_height: u32,
+    // changed since the last upload
+    dirty: bool,
+}
+
+impl GlyphAtlas {
+    const INITIAL_SIZE: u32 = 512;
+    const PADDING: u32 = 1;
+
+    fn new(max_size: u32) -> Self {
+        let size = Self::INITIAL_SIZE.min(max_size);
+        Self {
+            image: RgbaImage::new(size, size),
+            max_size,
+            glyphs: HashMap::new(),
+            cursor: (0, 0),
+            shelf_height: 0,
+            dirty: true,
+        }
+    }
+
+    // Rasterizes the glyph unless it is cached already. When the atlas
+    // can not grow any further it starts over empty, glyphs queued
+    // earlier in the frame are then skipped for this one frame.
+    fn ensure(&mut self, font: &fontdue::Font, key: GlyphKey) {
+        if self.glyphs.contains_key(&key) {
+            return;
+        }
+        let (metrics, coverage) = font.rasterize_indexed(key.index, f32::from_bits(key.size_bits));
+        let (width, height) = (metrics.width as u32, metrics.height as u32);
+        let Some((x, y)) = self.allocate(width, height).or_else(|| {
+            self.reset();
+            self.allocate(width, height)
+        }) else {
+            return;
+        };
+
+        // white glyphs with their coverage as alpha, tinted when drawn
+        let glyph = RgbaImage::from_fn(width, height, |gx, gy| {
+            Rgba([255, 255, 255, coverage[(gy * width + gx) as usize]])
+        });
+        self.image
+            .copy_from(&glyph, x, y)
+            .expect("Allocated glyph area lies inside the atlas");
+        self.glyphs.insert(key, (x, y, width, height));
+        self.dirty = true;
+    }
+
+    fn allocate(&mut self, width: u32, height: u32) -> Option<(u32, u32)> {
+        loop {
+            let (atlas_width, atlas_height) = self.image.dimensions();
+            if self.cursor.0 + width > atlas_width {
+                self.cursor = (0, self.cursor.1 + self.shelf_height + Self::PADDING);
+                self.shelf_height = 0;
+            }
+            if width <= atlas_width && self.cursor.1 + height <= atlas_height {
+                let position = self.cursor;
+                self.cursor.0 += width + Self::PADDING;
+                self.shelf_height = self.shelf_height.max(height);
+                return Some(position);
+            }
+            if !self.grow() {
+                return None;
+            }
+        }
+    }
+
+    // Doubles the size of the atlas, keeping the glyphs where they are
+    fn grow(&mut self) -> bool {
+        let size = self.image.width() * 2;
+        if size > self.max_size {
+            return false;
+        }
+        let mut image = RgbaImage::new(size, size);
+        image
+            .copy_from(&self.image, 0, 0)
+            .expect("Old atlas fits into the grown one");
+        self.image = image;
+        true
+    }
+
+    fn reset(&mut self) {
+        self.image = RgbaImage::new(self.image.width(), self.image.height());
+        self.glyphs.clear();
+        self.cursor = (0, 0);
+        self.shelf_height = 0;
+        self.dirty = true;
+    }
+
+    fn uv_rect(&self, (x, y, width, height): (u32, u32, u32, u32)) -> Rect {
+        let (atlas_width, atlas_height) = self.image.dimensions();
+        Rect::new(
+            x as f32 / atlas_width as f32,
+            y as f32 / atlas_height as f32,
+            width as f32 / atlas_width as f32,
+            height as f32 / atlas_height as f32,
+        )
+    }
+}
+
+// === QueuedGlyph struct
+struct QueuedGlyph {
+    key: GlyphKey,
+    // centre and size in screen pixels
+    position: Vector2<f32>,
+    size: Vector2<f32>,
+    color: [f32; 4],
+}
+
+// === TextRenderer struct
+pub(crate) struct TextRenderer {
+    atlas: GlyphAtlas,
+    // holds only the atlas texture, kept apart from the game's textures
+    textures: TextureRegistry,
+    atlas_texture: Option<TextureHandle>,
+    texture_layout: wgpu::BindGroupLayout,
+
+    camera_buffer: wgpu::Buffer,
+    camera_bind_group: wgpu::BindGroup,
+
+    sprites: SpriteRenderer,
+    glyphs: Vec<QueuedGlyph>,
+}
+
+impl TextRenderer {
+    pub(crate) fn new(
+        device: &wgpu::Device,
+        layouts: &SharedLayouts,
+        settings: &PipelineSettings,
+        lights: &wgpu::Buffer,
+    ) -> Self {
+        let camera_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Text Camera Buffer"),
+            contents: bytemuck::cast_slice(&[CameraUniform::new()]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let camera_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("text_camera_bind_group"),
+            layout: layouts.camera,
+            // text is unlit, the lights are only bound
+            // because the camera layout has them
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: camera_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: lights.as_entire_binding(),
+                },
+            ],
+        });
+
+        Self {
+            atlas: GlyphAtlas::new(device.limits().max_texture_dimension_2d.min(4096)),
+            textures: TextureRegistry::new(),
+            atlas_texture: None,
+            texture_layout: layouts.texture.clone(),
+            camera_buffer,
+            camera_bind_group,
+            sprites: SpriteRenderer::new(device, layouts, settings),
+            glyphs: Vec::new(),
+        }
+    }
+
+    pub(crate) fn rebuild_pipeline(&mut self, device: &wgpu::Device, settings: &PipelineSettings) {
+        self.sprites.rebuild_pipeline(device, settings);
+    }
+
+    // Rasterizes the glyphs of the laid out text and queues them
+    pub(crate) fn push(
+        &mut self,
+        font_handle: FontHandle,
+        font: &fontdue::Font,
+        layout: &TextLayout,
+        size: f32,
+        color: [f32; 4],
+    ) {
+        for glyph in &layout.glyphs {
+            let key = GlyphKey {
+                font: font_handle,
+                index: glyph.index,
+                size_bits: size.to_bits(),
+            };
+            self.atlas.ensure(font, key);
+            let size = Vector2::new(glyph.width, glyph.height);
+            self.glyphs.push(QueuedGlyph {
+                key,
+                position: Vector2::new(glyph.x, glyph.y) + size / 2.0,
+                size,
+                color,
+            });
+        }
+    }
+
+    // Uploads the atlas if glyphs were added and turns the queued
+    // glyphs into sprites
+    pub(crate) fn prepare(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        (width, height): (u32, u32),
+    ) {
+        if self.glyphs.is_empty() {
+            return;
+        }
+        if self.atlas.dirty {
+            self.upload_atlas(device, queue);
+        }
+        let Some(texture) = self.atlas_texture else {
+            return;
+        };
+
+        // looking down +z with y pointing down puts (0, 0) at the
+        // top left corner and keeps x pointing right
+        let (width, height) = (width as f32, height as f32);
+        let camera = Camera::orthographic(
+            (width / 2.0, height / 2.0, -1.0).into(),
+            (width / 2.0, height / 2.0, 0.0).into(),
+            -Vector3::unit_y(),
+            width / height,
+            height,
+            0.0,
+            2.0,
+        );
+        let mut camera_uniform = CameraUniform::new();
+        camera_uniform.update_view_proj(&camera);
+        queue.write_buffer(
+            &self.camera_buffer,
+            0,
+            bytemuck::cast_slice(&[camera_uniform]),
+        );
+
+        let atlas = &self.atlas;
+        self.sprites.push(self.glyphs.iter().filter_map(|glyph| {
+            let area = atlas.glyphs.get(&glyph.key)?;
+            Some(Sprite {
+                texture,
+                position: glyph.position,
+                // sprites expect y to point up, flip them back
+                size: Vector2::new(glyph.size.x, -glyph.size.y),
+                rotation: Rad(0.0),
+                uv_rect: atlas.uv_rect(*area),
+                tint: glyph.color,
+                layer: 0,
+            })
+        }));
+        self.sprites.prepare(device, queue, &self.textures);
+    }
+
+    fn upload_atlas(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        let options = TextureOptions {
+            srgb: false,
+            generate_mips: false,
+            ..Default::default()
+        };
+        let Ok(texture) = Texture::from_image(
+            device,
+            queue,
+            &DynamicImage::ImageRgba8(self.atlas.image.clone()),
+            Some("Glyph Atlas"),
+            &options,
+        ) else {
+            return;
+        };
+        let loaded = LoadedTexture {
+            bind_group: texture.create_bind_group(device, &self.texture_layout),
+            texture,
+            label: "Glyph Atlas".into(),
+            options,
+        };
+        match self.atlas_texture {
+            Some(handle) => {
+                self.textures.replace(handle, loaded);
+            }
+            None => self.atlas_texture = Some(self.textures.insert("Glyph Atlas", loaded)),
+        }
+        self.atlas.dirty = false;
+    }
+
+    pub(crate) fn draw(&self, render_pass: &mut wgpu::RenderPass) {
+        render_pass.set_bind_group(CAMERA_BIND_GROUP, &self.camera_bind_group, &[]);
+        self.sprites.draw(render_pass, &self.textures);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.glyphs.clear();
+        self.sprites.clear();
+    }
+
+    #[cfg(test)]
+    pub(crate) fn batch_count(&self) -> usize {
+        self.sprites.batch_count()
+    }
+}
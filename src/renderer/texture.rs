@@ -5,9 +5,109 @@
 //! Texture struct
 //!
 
+use std::collections::HashMap;
+
 use anyhow::*;
 use image::GenericImageView;
 
+use super::{
+    mipmap::{MipmapCache, MipmapGenerator},
+    registry::Registry,
+};
+
+// === TextureHandle struct
+// Returned from Renderer::load_texture and used to refer to the
+// texture when submitting draws.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextureHandle(pub(crate) u32);
+
+// === TextureOptions struct
+// How a texture is stored and sampled. Anisotropic filtering is only
+// applied when all three filters are Linear, as wgpu requires.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextureOptions {
+    pub address_mode_u: wgpu::AddressMode,
+    pub address_mode_v: wgpu::AddressMode,
+    pub mag_filter: wgpu::FilterMode,
+    pub min_filter: wgpu::FilterMode,
+    pub mipmap_filter: wgpu::FilterMode,
+    pub anisotropy: u16,
+    pub srgb: bool,
+    pub generate_mips: bool,
+}
+
+impl Default for TextureOptions {
+    // Trilinear filtering with a full mip chain, which suits most
+    // color textures seen at different distances.
+    fn default() -> Self {
+        Self {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            anisotropy: 1,
+            srgb: true,
+            generate_mips: true,
+        }
+    }
+}
+
+impl TextureOptions {
+    // Crisp texels without mips, for pixel art and UI
+    pub fn pixel_art() -> Self {
+        Self {
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            generate_mips: false,
+            ..Default::default()
+        }
+    }
+
+    // Repeats the texture outside of the 0..1 uv range
+    pub fn tiling() -> Self {
+        Self {
+            address_mode_u: wgpu::AddressMode::Repeat,
+            address_mode_v: wgpu::AddressMode::Repeat,
+            ..Default::default()
+        }
+    }
+
+    // Texture data that is not a color, e.g. normal maps, has to
+    // be stored without the sRGB transfer function
+    pub fn linear() -> Self {
+        Self {
+            srgb: false,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_anisotropy(mut self, anisotropy: u16) -> Self {
+        self.anisotropy = anisotropy;
+        self
+    }
+
+    fn format(&self) -> wgpu::TextureFormat {
+        if self.srgb {
+            wgpu::TextureFormat::Rgba8UnormSrgb
+        } else {
+            wgpu::TextureFormat::Rgba8Unorm
+        }
+    }
+
+    fn anisotropy_clamp(&self) -> u16 {
+        let all_linear = [self.mag_filter, self.min_filter, self.mipmap_filter]
+            .iter()
+            .all(|filter| *filter == wgpu::FilterMode::Linear);
+        if all_linear {
+            self.anisotropy.clamp(1, 16)
+        } else {
+            1
+        }
+    }
+}
+
 // === Texture Struct
 pub struct Texture {
     #[allow(unused)]
@@ -17,14 +117,70 @@ pub struct Texture {
 }
 
 impl Texture {
+    pub const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
+    // Depth attachment matching the size and sample count
+    // of the render target
+    pub fn create_depth_texture(
+        device: &wgpu::Device,
+        (width, height): (u32, u32),
+        sample_count: u32,
+        label: &str,
+    ) -> Self {
+        let size = wgpu::Extent3d {
+            width: width.max(1),
+            height: height.max(1),
+            depth_or_array_layers: 1,
+        };
+
+        // multisampled depth can not be sampled with a comparison sampler,
+        // and mixing it with renderbuffer backed attachments breaks on gl
+        let usage = if sample_count > 1 {
+            wgpu::TextureUsages::RENDER_ATTACHMENT
+        } else {
+            wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING
+        };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size,
+            mip_level_count: 1,
+            sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: Self::DEPTH_FORMAT,
+            usage,
+            view_formats: &[],
+        });
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            compare: Some(wgpu::CompareFunction::LessEqual),
+            lod_min_clamp: 0.0,
+            lod_max_clamp: 100.0,
+            ..Default::default()
+        });
+
+        Self {
+            texture,
+            view,
+            sampler,
+        }
+    }
+
     pub fn from_bytes(
         device: &wgpu::Device,
         queue: &wgpu::Queue,
         bytes: &[u8],
         label: &str,
+        options: &TextureOptions,
     ) -> Result<Self> {
         let img = image::load_from_memory(bytes)?;
-        Self::from_image(device, queue, &img, Some(label))
+        Self::from_image(device, queue, &img, Some(label), options)
     }
 
     pub fn from_image(
@@ -32,6 +188,32 @@ impl Texture {
         queue: &wgpu::Queue,
         img: &image::DynamicImage,
         label: Option<&str>,
+        options: &TextureOptions,
+    ) -> Result<Self> {
+        Self::from_image_cached(device, queue, img, label, options, &mut MipmapCache::new())
+    }
+
+    // Same as from_bytes() reusing the mip generators of the renderer
+    pub(crate) fn from_bytes_cached(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        bytes: &[u8],
+        label: &str,
+        options: &TextureOptions,
+        mipmaps: &mut MipmapCache,
+    ) -> Result<Self> {
+        let img = image::load_from_memory(bytes)?;
+        Self::from_image_cached(device, queue, &img, Some(label), options, mipmaps)
+    }
+
+    // Same as from_image() reusing the mip generators of the renderer
+    pub(crate) fn from_image_cached(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        img: &image::DynamicImage,
+        label: Option<&str>,
+        options: &TextureOptions,
+        mipmaps: &mut MipmapCache,
     ) -> Result<Self> {
         let rgba = img.to_rgba8();
         let dimensions = img.dimensions();
@@ -42,14 +224,24 @@ impl Texture {
             depth_or_array_layers: 1,
         };
 
+        let format = options.format();
+        let (mip_level_count, usage) = if options.generate_mips {
+            (
+                MipmapGenerator::mip_level_count(dimensions.0, dimensions.1),
+                wgpu::TextureUsages::RENDER_ATTACHMENT,
+            )
+        } else {
+            (1, wgpu::TextureUsages::empty())
+        };
+
         let texture = device.create_texture(&wgpu::TextureDescriptor {
             label,
             size,
-            mip_level_count: 1,
+            mip_level_count,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8UnormSrgb,
-            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            format,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST | usage,
             view_formats: &[],
         });
 
@@ -69,14 +261,21 @@ impl Texture {
             size,
         );
 
+        if mip_level_count > 1 {
+            mipmaps
+                .get(device, format)
+                .generate(device, queue, &texture);
+        }
+
         let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
         let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
-            address_mode_u: wgpu::AddressMode::ClampToBorder,
-            address_mode_v: wgpu::AddressMode::ClampToBorder,
-            address_mode_w: wgpu::AddressMode::ClampToBorder,
-            mag_filter: wgpu::FilterMode::Linear,
-            min_filter: wgpu::FilterMode::Nearest,
-            mipmap_filter: wgpu::FilterMode::Nearest,
+            address_mode_u: options.address_mode_u,
+            address_mode_v: options.address_mode_v,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: options.mag_filter,
+            min_filter: options.min_filter,
+            mipmap_filter: options.mipmap_filter,
+            anisotropy_clamp: options.anisotropy_clamp(),
             ..Default::default()
         });
 
@@ -87,3 +286,88 @@ impl Texture {
         })
     }
 }
+
+impl Texture {
+    // Bind group sampling the texture, in the layout of the
+    // texture bind group every pipeline shares
+    pub(crate) fn create_bind_group(
+        &self,
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&self.view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+            ],
+            label: Some("diffuse_bind_group"),
+        })
+    }
+}
+
+// === LoadedTexture struct
+// A texture owned by the renderer together with the bind group
+// used to sample it in the pipeline.
+pub(crate) struct LoadedTexture {
+    pub(crate) texture: Texture,
+    // name or path it was loaded with, kept as the debug label
+    pub(crate) label: String,
+    pub(crate) options: TextureOptions,
+    pub(crate) bind_group: wgpu::BindGroup,
+}
+
+// === TextureRegistry struct
+// Textures owned by the renderer. They are addressed by handle, and
+// can also be looked up by the name they were loaded with.
+pub(crate) struct TextureRegistry {
+    textures: Registry<LoadedTexture>,
+    names: HashMap<String, TextureHandle>,
+}
+
+impl TextureRegistry {
+    pub(crate) fn new() -> Self {
+        Self {
+            textures: Registry::new(),
+            names: HashMap::new(),
+        }
+    }
+
+    // Loading a name that is already in use makes the name point at the
+    // new texture, the old handle stays valid until it is unloaded.
+    pub(crate) fn insert(&mut self, name: &str, texture: LoadedTexture) -> TextureHandle {
+        let handle = TextureHandle(self.textures.insert(texture));
+        self.names.insert(name.to_owned(), handle);
+        handle
+    }
+
+    pub(crate) fn get(&self, handle: TextureHandle) -> Option<&LoadedTexture> {
+        self.textures.get(handle.0)
+    }
+
+    pub(crate) fn find(&self, name: &str) -> Option<TextureHandle> {
+        self.names.get(name).copied()
+    }
+
+    // Swaps the texture behind an existing handle, returning the old one
+    pub(crate) fn replace(
+        &mut self,
+        handle: TextureHandle,
+        texture: LoadedTexture,
+    ) -> Option<LoadedTexture> {
+        self.textures
+            .get_mut(handle.0)
+            .map(|slot| std::mem::replace(slot, texture))
+    }
+
+    pub(crate) fn remove(&mut self, handle: TextureHandle) -> Option<LoadedTexture> {
+        self.names.retain(|_, h| *h != handle);
+        self.textures.remove(handle.0)
+    }
+}
@@ -2,11 +2,22 @@
 //!
 //! Author: BEKs => 26.11.2025
 //!
-//! Vertex struct
+//! Vertex types meshes can be uploaded with
 //!
 use bytemuck::{Pod, Zeroable};
 use wgpu::{BufferAddress, VertexBufferLayout};
 
+// === VertexLayout trait
+// Describes how the vertex shader reads a vertex type. Any Pod struct
+// can implement it to be uploaded with Renderer::upload_mesh and drawn
+// with a material created for it, see MaterialDescriptor::with_vertex.
+// Locations 5 and up are taken by the instance data.
+pub trait VertexLayout: Pod {
+    fn desc() -> VertexBufferLayout<'static>;
+}
+
+// === Vertex struct
+// Position and texture coordinates, used by the basic material
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Pod, Zeroable)]
 pub struct Vertex {
@@ -24,8 +35,10 @@ impl Vertex {
             tex_coords,
         }
     }
+}
 
-    pub fn desc<'a>() -> VertexBufferLayout<'a> {
+impl VertexLayout for Vertex {
+    fn desc() -> VertexBufferLayout<'static> {
         VertexBufferLayout {
             array_stride: std::mem::size_of::<Vertex>() as BufferAddress,
             step_mode: wgpu::VertexStepMode::Vertex,
@@ -33,3 +46,107 @@ impl Vertex {
         }
     }
 }
+
+// === NormalVertex struct
+// Position, normal and texture coordinates for lit meshes
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub struct NormalVertex {
+    position: [f32; 3],
+    normal: [f32; 3],
+    tex_coords: [f32; 2],
+}
+
+impl NormalVertex {
+    const ATTRIB: [wgpu::VertexAttribute; 3] =
+        wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x3, 2 => Float32x2];
+
+    pub const fn new(position: [f32; 3], normal: [f32; 3], tex_coords: [f32; 2]) -> Self {
+        Self {
+            position,
+            normal,
+            tex_coords,
+        }
+    }
+}
+
+impl VertexLayout for NormalVertex {
+    fn desc() -> VertexBufferLayout<'static> {
+        VertexBufferLayout {
+            array_stride: std::mem::size_of::<NormalVertex>() as BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIB,
+        }
+    }
+}
+
+// === ColorVertex struct
+// Position and rgba color for untextured, vertex colored meshes
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub struct ColorVertex {
+    position: [f32; 3],
+    color: [f32; 4],
+}
+
+impl ColorVertex {
+    const ATTRIB: [wgpu::VertexAttribute; 2] =
+        wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x4];
+
+    pub const fn new(position: [f32; 3], color: [f32; 4]) -> Self {
+        Self { position, color }
+    }
+}
+
+impl VertexLayout for ColorVertex {
+    fn desc() -> VertexBufferLayout<'static> {
+        VertexBufferLayout {
+            array_stride: std::mem::size_of::<ColorVertex>() as BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIB,
+        }
+    }
+}
+
+// === PbrVertex struct
+// Everything normal mapped materials need. The w component of the
+// tangent is 1 or -1 and gives the handedness of the bitangent,
+// as in glTF.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub struct PbrVertex {
+    position: [f32; 3],
+    normal: [f32; 3],
+    tex_coords: [f32; 2],
+    tangent: [f32; 4],
+}
+
+impl PbrVertex {
+    const ATTRIB: [wgpu::VertexAttribute; 4] = wgpu::vertex_attr_array![
+        0 => Float32x3, 1 => Float32x3, 2 => Float32x2, 3 => Float32x4
+    ];
+
+    pub const fn new(
+        position: [f32; 3],
+        normal: [f32; 3],
+        tex_coords: [f32; 2],
+        tangent: [f32; 4],
+    ) -> Self {
+        Self {
+            position,
+            normal,
+            tex_coords,
+            tangent,
+        }
+    }
+}
+
+impl VertexLayout for PbrVertex {
+    fn desc() -> VertexBufferLayout<'static> {
+        VertexBufferLayout {
+            array_stride: std::mem::size_of::<PbrVertex>() as BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIB,
+        }
+    }
+}
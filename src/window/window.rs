@@ -93,6 +93,20 @@ impl Window {
         }
     }
 
+    pub fn vsync(&self) -> bool {
+        self.vsync
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    // Keeps the stored size in sync with the os window
+    pub(crate) fn resized(&mut self, width: u32, height: u32) {
+        self.width = width;
+        self.height = height;
+    }
+
     pub(crate) fn inner(&self) -> &WinWindow {
         &self.inner
     }